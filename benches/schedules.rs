@@ -0,0 +1,49 @@
+use pc_rs::linalg::vector::Vector;
+use pc_rs::{Activation, GaussianFunction, GaussianVariable, Graph, Matrix, Schedule};
+
+const BATCH_SIZE: usize = 64;
+const LAYER_SIZES: [usize; 5] = [16, 64, 64, 64, 8];
+const NUM_ITERATIONS: usize = 200;
+const REPORT_EVERY: usize = 50;
+const SCHEDULES: [Schedule; 4] = [
+    Schedule::Synchronous,
+    Schedule::Topological,
+    Schedule::Reverse,
+    Schedule::Random,
+];
+
+// A deep chain clamped at both ends, so inference has to carry the error through
+// every hidden layer.
+fn chain(schedule: Schedule) -> Graph<GaussianVariable, GaussianFunction> {
+    let mut graph = Graph::with_seed(0);
+    graph.set_schedule(schedule);
+    let mut indices = Vec::new();
+    for (i, &size) in LAYER_SIZES.iter().enumerate() {
+        let fixed = i == 0 || i == LAYER_SIZES.len() - 1;
+        let data = Matrix::normal_with_rng(BATCH_SIZE, size, 0.0, 1.0, graph.rng());
+        indices.push(graph.add_node(GaussianVariable::new(data, fixed)));
+    }
+    for i in 0..LAYER_SIZES.len() - 1 {
+        let (inp, out) = (LAYER_SIZES[i], LAYER_SIZES[i + 1]);
+        let weights =
+            Matrix::normal_with_rng(inp, out, 0.0, 1.0 / (inp as f64).sqrt(), graph.rng());
+        let function = GaussianFunction::new(weights, Vector::zeros(out), Activation::Tanh);
+        graph.add_edge(indices[i], indices[i + 1], function);
+    }
+    graph
+}
+
+fn main() {
+    for &schedule in SCHEDULES.iter() {
+        let mut graph = chain(schedule);
+        let mut energies = vec![graph.energy()];
+        for iteration in 1..=NUM_ITERATIONS {
+            graph.infer();
+            if iteration % REPORT_EVERY == 0 {
+                energies.push(graph.energy());
+            }
+        }
+        let energies: Vec<String> = energies.iter().map(|e| format!("{:>10.2}", e)).collect();
+        println!("{:<12} {}", format!("{:?}", schedule), energies.join(" "));
+    }
+}
@@ -0,0 +1,51 @@
+use std::time::{Duration, Instant};
+
+use pc_rs::linalg::vector::Vector;
+use pc_rs::{Activation, GaussianFunction, GaussianVariable, Graph, Matrix};
+
+const BATCH_SIZE: usize = 64;
+const LATENT_SIZE: usize = 32;
+const DATA_SIZE: usize = 64;
+const NUM_ITERATIONS: usize = 20;
+const WIDTHS: [usize; 3] = [8, 32, 128];
+
+// A single observed node predicted by `width` independent latent nodes.
+fn wide_graph(width: usize, parallel: bool) -> Graph<GaussianVariable, GaussianFunction> {
+    let mut graph = Graph::with_seed(0);
+    graph.set_parallel(parallel);
+    let data = Matrix::ones(BATCH_SIZE, DATA_SIZE);
+    let data_index = graph.add_node(GaussianVariable::new(data, true));
+    for _ in 0..width {
+        let mu = Matrix::normal_with_rng(BATCH_SIZE, LATENT_SIZE, 0.0, 0.05, graph.rng());
+        let weights = Matrix::normal_with_rng(LATENT_SIZE, DATA_SIZE, 0.0, 0.05, graph.rng());
+        let mu_index = graph.add_node(GaussianVariable::new(mu, false));
+        let function = GaussianFunction::new(weights, Vector::zeros(DATA_SIZE), Activation::Tanh);
+        graph.add_edge(mu_index, data_index, function);
+    }
+    graph
+}
+
+fn run(width: usize, parallel: bool) -> Duration {
+    let mut graph = wide_graph(width, parallel);
+    let start = Instant::now();
+    for _ in 0..NUM_ITERATIONS {
+        graph.infer();
+    }
+    graph.learn();
+    start.elapsed()
+}
+
+fn main() {
+    println!("threads: {}", rayon::current_num_threads());
+    for &width in WIDTHS.iter() {
+        let serial = run(width, false);
+        let parallel = run(width, true);
+        println!(
+            "width {:>4}: serial {:>8.2?}  parallel {:>8.2?}  speed-up {:.2}x",
+            width,
+            serial,
+            parallel,
+            serial.as_secs_f64() / parallel.as_secs_f64()
+        );
+    }
+}
@@ -0,0 +1,55 @@
+use pc_rs::categorical::variable::{argmax, one_hot};
+use pc_rs::{Activation, Matrix, Mlp};
+
+const INPUT_SIZE: usize = 2;
+const HIDDEN_SIZE: usize = 32;
+const NUM_CLASSES: usize = 2;
+const BATCH_SIZE: usize = 64;
+const NUM_EPOCHS: usize = 3000;
+const NUM_ITERATIONS: usize = 10;
+const SEED: u64 = 0;
+
+// Points are labelled by the sign of `x0 * x1`, which a linear model cannot fit.
+fn labels(inputs: &Matrix) -> Vec<usize> {
+    inputs
+        .data
+        .iter()
+        .map(|row| (row[0] * row[1] > 0.0) as usize)
+        .collect()
+}
+
+fn accuracy(preds: &Matrix, labels: &[usize]) -> f64 {
+    let correct = argmax(preds)
+        .iter()
+        .zip(labels.iter())
+        .filter(|(pred, label)| pred == label)
+        .count();
+    correct as f64 / labels.len() as f64
+}
+
+fn main() {
+    let sizes = [INPUT_SIZE, HIDDEN_SIZE, HIDDEN_SIZE, NUM_CLASSES];
+    let activations = [Activation::Tanh, Activation::Tanh, Activation::Linear];
+    let mut mlp = Mlp::with_seed(&sizes, &activations, SEED);
+
+    let test_inputs =
+        Matrix::normal_with_rng(BATCH_SIZE * 4, INPUT_SIZE, 0.0, 1.0, mlp.graph_mut().rng());
+    let test_labels = labels(&test_inputs);
+
+    for epoch in 0..NUM_EPOCHS {
+        let inputs =
+            Matrix::normal_with_rng(BATCH_SIZE, INPUT_SIZE, 0.0, 1.0, mlp.graph_mut().rng());
+        let targets = one_hot(&labels(&inputs), NUM_CLASSES);
+        let energy = mlp.train_batch(&inputs, &targets, NUM_ITERATIONS);
+
+        if (epoch + 1) % 500 == 0 {
+            let preds = mlp.predict(&test_inputs, 0);
+            println!(
+                "epoch {:>4}  energy {:>8.4}  test accuracy {:.3}",
+                epoch + 1,
+                energy,
+                accuracy(&preds, &test_labels)
+            );
+        }
+    }
+}
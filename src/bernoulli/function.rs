@@ -0,0 +1,104 @@
+use rand::Rng;
+
+use crate::graph::{params::LinearParams, Function, Variable};
+use crate::linalg::{
+    init::Init,
+    math::{Activation, Nonlinearity},
+    matrix::Matrix,
+    vector::Vector,
+};
+
+// Predicts Bernoulli means `sigmoid(x W + b)` and scores targets with binary
+// cross-entropy, so it can sit on any edge whose target holds values in `[0, 1]`.
+pub struct BernoulliFunction {
+    params: LinearParams,
+    fixed: bool,
+}
+
+impl BernoulliFunction {
+    pub fn new(weights: Matrix, bias: Vector) -> Self {
+        assert_eq!(weights.cols, bias.size);
+        Self {
+            params: LinearParams { weights, bias },
+            fixed: false,
+        }
+    }
+
+    pub fn from_init(inp_size: usize, out_size: usize, init: Init) -> Self {
+        Self::from_init_with_rng(inp_size, out_size, init, &mut rand::thread_rng())
+    }
+
+    pub fn from_init_with_rng(
+        inp_size: usize,
+        out_size: usize,
+        init: Init,
+        rng: &mut impl Rng,
+    ) -> Self {
+        let weights = Matrix::init_with_rng(inp_size, out_size, init, rng);
+        Self::new(weights, Vector::zeros(out_size))
+    }
+
+    pub fn weights(&self) -> &Matrix {
+        &self.params.weights
+    }
+
+    pub fn bias(&self) -> &Vector {
+        &self.params.bias
+    }
+
+    fn logits(&self, inp: &Matrix) -> Matrix {
+        assert_eq!(inp.cols, self.params.weights.rows);
+        &inp.matmul(&self.params.weights) + &self.params.bias
+    }
+}
+
+impl<T: Variable<Gradient = Matrix>> Function<T> for BernoulliFunction {
+    type Params = LinearParams;
+    type Gradient = LinearParams;
+
+    fn forward(&self, inp: &T) -> Matrix {
+        Activation::Sigmoid.forward(&self.logits(inp.data()))
+    }
+
+    // Binary cross-entropy written in terms of the logits for numerical stability.
+    fn energy(&self, inp: &T, target: &T) -> f64 {
+        let logits = self.logits(inp.data());
+        let log_partition = Activation::Softplus.forward(&logits);
+        let energy = &log_partition - &(target.data() * &logits);
+        energy.data.iter().flatten().sum()
+    }
+
+    fn backward(&self, inp: &T, target: &T) -> (Matrix, Matrix) {
+        let logits = self.logits(inp.data());
+        let err = target.data() - &Activation::Sigmoid.forward(&logits);
+        let source_deriv = err.matmul(&self.params.weights.transpose());
+        let target_deriv = logits;
+        (source_deriv, target_deriv)
+    }
+
+    fn backward_params(&self, inp: &T, target: &T) -> LinearParams {
+        let logits = self.logits(inp.data());
+        let err = target.data() - &Activation::Sigmoid.forward(&logits);
+        let weight_deriv = inp.data().transpose().matmul(&err);
+        let bias_deriv = err.sum(0);
+        LinearParams {
+            weights: weight_deriv,
+            bias: bias_deriv,
+        }
+    }
+
+    fn params(&self) -> &LinearParams {
+        &self.params
+    }
+
+    fn params_mut(&mut self) -> &mut LinearParams {
+        &mut self.params
+    }
+
+    fn update(&mut self, derivatives: LinearParams) {
+        if !self.fixed {
+            self.params.weights += &derivatives.weights.apply(|v| v * 0.001);
+            self.params.bias += &derivatives.bias.apply(|v| v * 0.001);
+        }
+    }
+}
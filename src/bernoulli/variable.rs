@@ -0,0 +1,4 @@
+use crate::graph::bounded::{BoundedVariable, UnitInterval};
+
+// Holds Bernoulli means in `[0, 1]`; observed nodes are clamped to binary data.
+pub type BernoulliVariable = BoundedVariable<UnitInterval>;
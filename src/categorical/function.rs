@@ -0,0 +1,131 @@
+use rand::Rng;
+
+use crate::graph::{params::LinearParams, Function, Variable};
+use crate::linalg::{
+    init::Init,
+    math::{Activation, Nonlinearity},
+    matrix::Matrix,
+    vector::Vector,
+};
+
+// Predicts class distributions `softmax(x W + b)` and scores targets with
+// cross-entropy.
+pub struct CategoricalFunction {
+    params: LinearParams,
+    fixed: bool,
+}
+
+impl CategoricalFunction {
+    pub fn new(weights: Matrix, bias: Vector) -> Self {
+        assert_eq!(weights.cols, bias.size);
+        Self {
+            params: LinearParams { weights, bias },
+            fixed: false,
+        }
+    }
+
+    pub fn from_init(inp_size: usize, num_classes: usize, init: Init) -> Self {
+        Self::from_init_with_rng(inp_size, num_classes, init, &mut rand::thread_rng())
+    }
+
+    pub fn from_init_with_rng(
+        inp_size: usize,
+        num_classes: usize,
+        init: Init,
+        rng: &mut impl Rng,
+    ) -> Self {
+        let weights = Matrix::init_with_rng(inp_size, num_classes, init, rng);
+        Self::new(weights, Vector::zeros(num_classes))
+    }
+
+    pub fn weights(&self) -> &Matrix {
+        &self.params.weights
+    }
+
+    pub fn bias(&self) -> &Vector {
+        &self.params.bias
+    }
+
+    fn logits(&self, inp: &Matrix) -> Matrix {
+        assert_eq!(inp.cols, self.params.weights.rows);
+        &inp.matmul(&self.params.weights) + &self.params.bias
+    }
+
+    // Error on the logits, `t - p * sum(t)`, which is `t - p` for normalised targets.
+    fn errors(&self, inp: &Matrix, target: &Matrix) -> (Matrix, Matrix) {
+        let logits = self.logits(inp);
+        let probs = Activation::Softmax.forward(&logits);
+        let mass = target.sum(1);
+        let mut err = Matrix::zeros(target.rows, target.cols);
+        for i in 0..target.rows {
+            for k in 0..target.cols {
+                err.data[i][k] = target.data[i][k] - probs.data[i][k] * mass.data[i];
+            }
+        }
+        (logits, err)
+    }
+}
+
+impl<T: Variable<Gradient = Matrix>> Function<T> for CategoricalFunction {
+    type Params = LinearParams;
+    type Gradient = LinearParams;
+
+    fn forward(&self, inp: &T) -> Matrix {
+        Activation::Softmax.forward(&self.logits(inp.data()))
+    }
+
+    fn energy(&self, inp: &T, target: &T) -> f64 {
+        let log_probs = log_softmax(&self.logits(inp.data()));
+        -(target.data() * &log_probs)
+            .data
+            .iter()
+            .flatten()
+            .sum::<f64>()
+    }
+
+    fn backward(&self, inp: &T, target: &T) -> (Matrix, Matrix) {
+        let (logits, err) = self.errors(inp.data(), target.data());
+        let source_deriv = err.matmul(&self.params.weights.transpose());
+        let target_deriv = log_softmax(&logits);
+        (source_deriv, target_deriv)
+    }
+
+    fn backward_params(&self, inp: &T, target: &T) -> LinearParams {
+        let (_, err) = self.errors(inp.data(), target.data());
+        let weight_deriv = inp.data().transpose().matmul(&err);
+        let bias_deriv = err.sum(0);
+        LinearParams {
+            weights: weight_deriv,
+            bias: bias_deriv,
+        }
+    }
+
+    fn params(&self) -> &LinearParams {
+        &self.params
+    }
+
+    fn params_mut(&mut self) -> &mut LinearParams {
+        &mut self.params
+    }
+
+    fn update(&mut self, derivatives: LinearParams) {
+        if !self.fixed {
+            self.params.weights += &derivatives.weights.apply(|v| v * 0.001);
+            self.params.bias += &derivatives.bias.apply(|v| v * 0.001);
+        }
+    }
+}
+
+fn log_softmax(logits: &Matrix) -> Matrix {
+    let data = logits
+        .data
+        .iter()
+        .map(|row| {
+            let max = row.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+            let total: f64 = row.iter().map(|v| (v - max).exp()).sum();
+            let log_partition = max + total.ln();
+            row.iter().map(|v| v - log_partition).collect()
+        })
+        .collect();
+    Matrix::new(data)
+}
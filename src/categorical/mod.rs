@@ -0,0 +1,62 @@
+pub mod function;
+pub mod variable;
+
+use crate::graph::{Function, Graph, Variable};
+use crate::linalg::matrix::Matrix;
+
+// Class distribution predicted for `node` by its incoming edges, averaged when more
+// than one edge targets it. Call after `Graph::infer` has settled the latents.
+pub fn class_distribution<T: Variable, F: Function<T>>(
+    graph: &Graph<T, F>,
+    node: usize,
+) -> Option<Matrix> {
+    let preds: Vec<Matrix> = graph
+        .get_edges()
+        .filter(|edge| edge.target == node)
+        .map(|edge| edge.function.forward(graph.get_node(edge.source).unwrap()))
+        .collect();
+
+    let count = preds.len() as f64;
+    let mut preds = preds.into_iter();
+    let first = preds.next()?;
+    let total = preds.fold(first, |total, pred| &total + &pred);
+    Some(total * (1.0 / count))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::categorical::{
+        function::CategoricalFunction,
+        variable::{argmax, CategoricalVariable},
+    };
+    use crate::linalg::vector::Vector;
+
+    #[test]
+    fn test_class_distribution_after_training() {
+        let features = Matrix::new(vec![
+            vec![1.0, 0.0],
+            vec![0.9, 0.1],
+            vec![0.0, 1.0],
+            vec![0.2, 0.8],
+        ]);
+        let labels = [0, 0, 1, 1];
+
+        let mut graph = Graph::<CategoricalVariable, CategoricalFunction>::with_seed(0);
+        let inp = graph.add_node(CategoricalVariable::new(features, true));
+        let out = graph.add_node(CategoricalVariable::from_labels(&labels, 2, true));
+        let function = CategoricalFunction::new(Matrix::zeros(2, 2), Vector::zeros(2));
+        graph.add_edge(inp, out, function);
+
+        for _ in 0..2000 {
+            graph.learn();
+        }
+
+        let distribution = class_distribution(&graph, out).unwrap();
+        for row in distribution.data.iter() {
+            assert!((row.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+        }
+        assert_eq!(argmax(&distribution), labels.to_vec());
+        assert!(class_distribution(&graph, inp).is_none());
+    }
+}
@@ -0,0 +1,135 @@
+use crate::graph::Variable;
+use crate::linalg::matrix::Matrix;
+
+// Each row is a distribution over classes; observed labels are clamped one-hot rows.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CategoricalVariable {
+    pub size: usize,
+    pub data: Matrix,
+    fixed: bool,
+}
+
+impl CategoricalVariable {
+    pub fn new(data: Matrix, fixed: bool) -> Self {
+        let size = data.cols;
+        Self { size, data, fixed }
+    }
+
+    pub fn from_labels(labels: &[usize], num_classes: usize, fixed: bool) -> Self {
+        Self::new(one_hot(labels, num_classes), fixed)
+    }
+
+    // Uninformative start for label nodes that are inferred rather than observed.
+    pub fn uniform(batch_size: usize, num_classes: usize) -> Self {
+        let data = Matrix::ones(batch_size, num_classes) * (1.0 / num_classes as f64);
+        Self::new(data, false)
+    }
+
+    pub fn set_data(&mut self, data: Matrix) {
+        assert_eq!(data.cols, self.size);
+        self.data = data;
+    }
+
+    pub fn classes(&self) -> Vec<usize> {
+        argmax(&self.data)
+    }
+}
+
+impl Variable for CategoricalVariable {
+    type Gradient = Matrix;
+
+    fn data(&self) -> &Matrix {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut Matrix {
+        &mut self.data
+    }
+
+    fn fixed(&self) -> bool {
+        self.fixed
+    }
+
+    fn set_fixed(&mut self, fixed: bool) {
+        self.fixed = fixed;
+    }
+
+    fn update(&mut self, derivatives: &[Matrix], rate: f64) {
+        if !self.fixed {
+            for derivative in derivatives {
+                self.data += &derivative.apply(|v| v * rate);
+            }
+            for row in self.data.data.iter_mut() {
+                project_simplex(row);
+            }
+        }
+    }
+}
+
+pub fn one_hot(labels: &[usize], num_classes: usize) -> Matrix {
+    let mut data = Matrix::zeros(labels.len(), num_classes);
+    for (i, &label) in labels.iter().enumerate() {
+        assert!(label < num_classes);
+        data.data[i][label] = 1.0;
+    }
+    data
+}
+
+pub fn argmax(distribution: &Matrix) -> Vec<usize> {
+    distribution
+        .data
+        .iter()
+        .map(|row| {
+            let mut best = 0;
+            for (k, &value) in row.iter().enumerate() {
+                if value > row[best] {
+                    best = k;
+                }
+            }
+            best
+        })
+        .collect()
+}
+
+// Euclidean projection onto the probability simplex.
+fn project_simplex(row: &mut [f64]) {
+    let mut sorted = row.to_vec();
+    sorted.sort_by(|a, b| b.partial_cmp(a).unwrap());
+    let mut total = 0.0;
+    let mut threshold = 0.0;
+    for (k, value) in sorted.iter().enumerate() {
+        total += value;
+        let candidate = (total - 1.0) / (k + 1) as f64;
+        if value - candidate > 0.0 {
+            threshold = candidate;
+        }
+    }
+    for value in row.iter_mut() {
+        *value = (*value - threshold).max(0.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_one_hot() {
+        let labels = one_hot(&[2, 0], 3);
+        assert_eq!(
+            labels,
+            Matrix::new(vec![vec![0.0, 0.0, 1.0], vec![1.0, 0.0, 0.0]])
+        );
+        assert_eq!(argmax(&labels), vec![2, 0]);
+    }
+
+    #[test]
+    fn test_project_simplex() {
+        let mut row = vec![0.5, 0.9, -0.2];
+        project_simplex(&mut row);
+        assert!((row.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+        assert!((row[0] - 0.3).abs() < 1e-12);
+        assert!((row[1] - 0.7).abs() < 1e-12);
+        assert_eq!(row[2], 0.0);
+    }
+}
@@ -0,0 +1,204 @@
+use std::fmt;
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+
+use crate::graph::{Function, Graph, Variable};
+
+// Graph parameters saved as text, one tensor per line: its name from
+// `Graph::parameters` (`<edge>.<name>` for edges, `node.<node>.<name>` for node-owned
+// tensors such as learnable priors), the number of values, then the values. Loading
+// checks that the names and sizes match the graph.
+
+const HEADER: &str = "# pc-rs checkpoint";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CheckpointError {
+    Io(String),
+    Invalid { line: usize, message: String },
+}
+
+impl fmt::Display for CheckpointError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CheckpointError::Io(message) => write!(f, "{}", message),
+            CheckpointError::Invalid { line, message } => {
+                write!(f, "line {}: {}", line, message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CheckpointError {}
+
+pub fn save<T: Variable, F: Function<T>>(
+    graph: &Graph<T, F>,
+    path: impl AsRef<Path>,
+) -> Result<(), CheckpointError> {
+    let path = path.as_ref();
+    let io_error = |error: io::Error| CheckpointError::Io(format!("{}: {}", path.display(), error));
+    let mut file = io::BufWriter::new(fs::File::create(path).map_err(io_error)?);
+    write(graph, &mut file).map_err(io_error)?;
+    file.flush().map_err(io_error)
+}
+
+pub fn write<T: Variable, F: Function<T>>(
+    graph: &Graph<T, F>,
+    writer: &mut impl Write,
+) -> io::Result<()> {
+    writeln!(writer, "{}", HEADER)?;
+    for (name, tensor) in graph.parameters() {
+        let values = tensor.flatten();
+        write!(writer, "{} {}", name, values.len())?;
+        for value in values {
+            write!(writer, " {}", value)?;
+        }
+        writeln!(writer)?;
+    }
+    Ok(())
+}
+
+pub fn load<T: Variable, F: Function<T>>(
+    graph: &mut Graph<T, F>,
+    path: impl AsRef<Path>,
+) -> Result<(), CheckpointError> {
+    let path = path.as_ref();
+    let source = fs::read_to_string(path)
+        .map_err(|error| CheckpointError::Io(format!("{}: {}", path.display(), error)))?;
+    read(graph, &source)
+}
+
+// The graph is only modified once the whole checkpoint has been validated.
+pub fn read<T: Variable, F: Function<T>>(
+    graph: &mut Graph<T, F>,
+    source: &str,
+) -> Result<(), CheckpointError> {
+    let mut lines = source
+        .lines()
+        .enumerate()
+        .map(|(index, text)| (index + 1, text.trim()))
+        .filter(|(_, text)| !text.is_empty());
+    let invalid = |line: usize, message: String| CheckpointError::Invalid { line, message };
+
+    match lines.next() {
+        Some((_, HEADER)) => {}
+        _ => return Err(invalid(1, "missing checkpoint header".to_string())),
+    }
+
+    let mut tensors = Vec::new();
+    for (name, tensor) in graph.parameters() {
+        let (line, text) = lines
+            .next()
+            .ok_or_else(|| invalid(0, format!("missing parameter `{}`", name)))?;
+        let mut fields = text.split_whitespace();
+        if fields.next() != Some(name.as_str()) {
+            return Err(invalid(line, format!("expected parameter `{}`", name)));
+        }
+
+        let count = tensor.flatten().len();
+        if fields.next() != Some(count.to_string().as_str()) {
+            let message = format!("expected {} values for `{}`", count, name);
+            return Err(invalid(line, message));
+        }
+        let values: Result<Vec<f64>, _> = fields.map(str::parse).collect();
+        match values {
+            Ok(values) if values.len() == count => tensors.push(values),
+            _ => return Err(invalid(line, format!("invalid values for `{}`", name))),
+        }
+    }
+    if let Some((line, _)) = lines.next() {
+        return Err(invalid(line, "unexpected parameter".to_string()));
+    }
+
+    for ((_, tensor), values) in graph.parameters_mut().into_iter().zip(tensors) {
+        for (param, value) in tensor.flatten_mut().into_iter().zip(values) {
+            *param = value;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gaussian::{
+        function::GaussianFunction, prior::GaussianPrior, variable::GaussianVariable,
+    };
+    use crate::linalg::{math::Activation, matrix::Matrix, vector::Vector};
+
+    fn graph(seed: u64) -> Graph<GaussianVariable, GaussianFunction> {
+        graph_with_prior(seed, None)
+    }
+
+    fn graph_with_prior(
+        seed: u64,
+        prior: Option<GaussianPrior>,
+    ) -> Graph<GaussianVariable, GaussianFunction> {
+        let mut graph = Graph::with_seed(seed);
+        let mut variable = GaussianVariable::new(Matrix::zeros(1, 3), false);
+        if let Some(prior) = prior {
+            variable = variable.with_prior(prior);
+        }
+        let a = graph.add_node(variable);
+        let b = graph.add_node(GaussianVariable::new(Matrix::zeros(1, 2), true));
+        let weights = Matrix::normal_with_rng(3, 2, 0.0, 1.0, graph.rng());
+        let function = GaussianFunction::new(weights, Vector::zeros(2), Activation::Tanh)
+            .with_diagonal_precision(Vector::new(vec![0.5, 2.0]));
+        graph.add_edge(a, b, function);
+        graph
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let saved = graph(0);
+        let mut buffer = Vec::new();
+        write(&saved, &mut buffer).unwrap();
+
+        let mut loaded = graph(1);
+        read(&mut loaded, &String::from_utf8(buffer).unwrap()).unwrap();
+        for ((_, a), (_, b)) in saved.parameters().iter().zip(loaded.parameters().iter()) {
+            assert_eq!(a.flatten(), b.flatten());
+        }
+    }
+
+    #[test]
+    fn test_roundtrip_learnable_prior() {
+        let prior = || GaussianPrior::learnable(Vector::zeros(3), Vector::new(vec![1.0; 3]));
+        let mut saved = graph_with_prior(0, Some(prior()));
+        saved.get_node_mut(0).unwrap().set_data(Matrix::ones(1, 3));
+        for _ in 0..10 {
+            saved.learn();
+        }
+        let mut buffer = Vec::new();
+        write(&saved, &mut buffer).unwrap();
+        let source = String::from_utf8(buffer).unwrap();
+        assert!(source.contains("node.0.prior.mean 3 "));
+        assert!(source.contains("node.0.prior.precision 3 "));
+
+        let mut loaded = graph_with_prior(1, Some(prior()));
+        read(&mut loaded, &source).unwrap();
+        let (saved, loaded) = (saved.get_node(0).unwrap(), loaded.get_node(0).unwrap());
+        assert_ne!(loaded.prior().unwrap().mean, Vector::zeros(3));
+        assert_eq!(loaded.prior(), saved.prior());
+
+        let mut unrelated = graph(1);
+        let error = read(&mut unrelated, &source).unwrap_err();
+        assert_eq!(error.to_string(), "line 5: unexpected parameter");
+    }
+
+    #[test]
+    fn test_mismatch() {
+        let mut loaded = graph(1);
+        let before: Vec<f64> = loaded.parameters()[0].1.flatten();
+
+        let source = format!("{}\n0.weights 5 1 2 3 4 5\n", HEADER);
+        let error = read(&mut loaded, &source).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 2: expected 6 values for `0.weights`"
+        );
+        let error = read(&mut loaded, "0.weights 6").unwrap_err();
+        assert_eq!(error.to_string(), "line 1: missing checkpoint header");
+        assert_eq!(loaded.parameters()[0].1.flatten(), before);
+    }
+}
@@ -0,0 +1,585 @@
+use std::fs;
+use std::io::{self, Write};
+
+use pc_rs::checkpoint;
+use pc_rs::checkpoint::CheckpointError;
+use pc_rs::config::NodeConfig;
+use pc_rs::data::csv;
+use pc_rs::data::csv::CsvError;
+use pc_rs::data::{DataLoader, MatrixDataset};
+use pc_rs::gaussian::builder::Handles;
+use pc_rs::{Config, Function, GaussianFunction, GaussianVariable, Graph, Matrix, Mode, Variable};
+
+pub const USAGE: &str = "usage: pc-rs <command> [options]
+
+commands:
+  train      train a model, printing the energy after every epoch
+  eval       report the energy of a trained model, and the error on any labels
+  infer      write the inferred latents and labels as CSV
+  inspect    print the nodes, edges and parameters of a model
+
+options:
+  --config <path>           model config [default: configs/demo.toml]
+  --data <path>             CSV dataset whose columns fill the observed and label
+                            nodes in config order, otherwise nodes use their `value`
+  --checkpoint <path>       checkpoint written by `train` and read by the others
+  --output <path>           file `infer` writes to [default: stdout]
+  --seed <n>                override the config's seed
+  --epochs <n>              override `training.num_epochs`
+  --iterations <n>          override `training.num_iterations`
+  --batch-size <n>          override `training.batch_size`
+  --learning-rate <x>       override `training.learning_rate`
+  --inference-rate <x>      override `training.inference_rate`";
+
+const DEFAULT_CONFIG: &str = "configs/demo.toml";
+
+#[derive(Default)]
+struct Options {
+    config: Option<String>,
+    data: Option<String>,
+    checkpoint: Option<String>,
+    output: Option<String>,
+    seed: Option<u64>,
+    epochs: Option<usize>,
+    iterations: Option<usize>,
+    batch_size: Option<usize>,
+    learning_rate: Option<f64>,
+    inference_rate: Option<f64>,
+}
+
+impl Options {
+    // Accepts both `--key value` and `--key=value`.
+    fn parse(args: &[String]) -> Result<Self, String> {
+        let mut options = Options::default();
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let (key, inline) = match arg.split_once('=') {
+                Some((key, value)) => (key, Some(value.to_string())),
+                None => (arg.as_str(), None),
+            };
+            if !key.starts_with("--") {
+                return Err(format!("unexpected argument `{}`", arg));
+            }
+            let value = match inline {
+                Some(value) => value,
+                None => args
+                    .next()
+                    .cloned()
+                    .ok_or_else(|| format!("missing value for `{}`", key))?,
+            };
+
+            match key {
+                "--config" => options.config = Some(value),
+                "--data" => options.data = Some(value),
+                "--checkpoint" => options.checkpoint = Some(value),
+                "--output" => options.output = Some(value),
+                "--seed" => options.seed = Some(number(key, &value)?),
+                "--epochs" => options.epochs = Some(number(key, &value)?),
+                "--iterations" => options.iterations = Some(number(key, &value)?),
+                "--batch-size" => options.batch_size = Some(number(key, &value)?),
+                "--learning-rate" => options.learning_rate = Some(number(key, &value)?),
+                "--inference-rate" => options.inference_rate = Some(number(key, &value)?),
+                _ => return Err(format!("unknown option `{}`", key)),
+            }
+        }
+        Ok(options)
+    }
+
+    fn config(&self) -> Result<Config, String> {
+        let path = self.config.as_deref().unwrap_or(DEFAULT_CONFIG);
+        let mut config = Config::from_file(path).map_err(|error| error.to_string())?;
+        let training = &mut config.training;
+        if let Some(seed) = self.seed {
+            config.seed = seed;
+        }
+        if let Some(epochs) = self.epochs {
+            training.num_epochs = epochs;
+        }
+        if let Some(iterations) = self.iterations {
+            training.num_iterations = iterations;
+        }
+        if let Some(batch_size) = self.batch_size {
+            if batch_size == 0 {
+                return Err("`--batch-size` must be positive".to_string());
+            }
+            training.batch_size = batch_size;
+        }
+        if let Some(learning_rate) = self.learning_rate {
+            training.learning_rate = learning_rate;
+        }
+        if let Some(inference_rate) = self.inference_rate {
+            training.inference_rate = inference_rate;
+        }
+        Ok(config)
+    }
+
+    fn checkpoint(&self, command: &str) -> Result<&str, String> {
+        self.checkpoint
+            .as_deref()
+            .ok_or_else(|| format!("`{}` requires `--checkpoint`", command))
+    }
+}
+
+fn number<N: std::str::FromStr>(key: &str, value: &str) -> Result<N, String> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid value `{}` for `{}`", value, key))
+}
+
+pub fn run(args: &[String]) -> Result<(), String> {
+    let (command, args) = match args.split_first() {
+        Some((command, args)) => (command.as_str(), args),
+        None => return Err(USAGE.to_string()),
+    };
+    if command == "-h" || command == "--help" || command == "help" {
+        println!("{}", USAGE);
+        return Ok(());
+    }
+
+    if !["train", "eval", "infer", "inspect"].contains(&command) {
+        return Err(format!("unknown command `{}`\n\n{}", command, USAGE));
+    }
+
+    let options = Options::parse(args)?;
+    let shuffle = command == "train";
+    let mut model = Model::new(options.config()?, options.data.as_deref(), shuffle)?;
+    match command {
+        "train" => {
+            model.train();
+            if let Some(path) = options.checkpoint.as_deref() {
+                checkpoint::save(&model.graph, path).map_err(|error| error.to_string())?;
+                println!("saved checkpoint to {}", path);
+            }
+        }
+        "eval" => {
+            model.load(options.checkpoint(command)?)?;
+            model.eval();
+        }
+        "infer" => {
+            model.load(options.checkpoint(command)?)?;
+            match options.output.as_deref() {
+                Some(path) => {
+                    let mut file = fs::File::create(path).map_err(|error| error.to_string())?;
+                    model.infer(&mut file)?;
+                }
+                None => model.infer(&mut io::stdout().lock())?,
+            }
+        }
+        "inspect" => {
+            if let Some(path) = options.checkpoint.as_deref() {
+                model.load(path)?;
+            }
+            model.inspect();
+        }
+        _ => unreachable!(),
+    }
+    Ok(())
+}
+
+struct Model {
+    config: Config,
+    graph: Graph<GaussianVariable, GaussianFunction>,
+    handles: Handles,
+    loader: Option<DataLoader<MatrixDataset>>,
+}
+
+impl Model {
+    // Training batches are shuffled, while `eval` and `infer` keep the dataset order.
+    fn new(config: Config, data: Option<&str>, shuffle: bool) -> Result<Self, String> {
+        let (graph, handles) = config.build();
+        let loader = match data {
+            Some(path) => {
+                let data = csv::read(path).map_err(|error| match error {
+                    CsvError::Io(message) => message,
+                    error => format!("{}: {}", path, error),
+                })?;
+                let sizes: Vec<usize> = data_nodes(&config).map(|node| node.size).collect();
+                let cols: usize = sizes.iter().sum();
+                if data.cols != cols {
+                    let message = "columns, but its observed and label nodes have";
+                    return Err(format!("{} has {} {} {}", path, data.cols, message, cols));
+                }
+                let dataset = MatrixDataset::from_columns(&data, &sizes);
+                let loader =
+                    DataLoader::with_seed(dataset, config.training.batch_size, config.seed);
+                Some(loader.with_shuffle(shuffle))
+            }
+            None => None,
+        };
+
+        Ok(Self {
+            config,
+            graph,
+            handles,
+            loader,
+        })
+    }
+
+    fn load(&mut self, path: &str) -> Result<(), String> {
+        checkpoint::load(&mut self.graph, path).map_err(|error| match error {
+            CheckpointError::Io(message) => message,
+            error => format!("{}: {}", path, error),
+        })
+    }
+
+    fn train(&mut self) {
+        self.graph.train();
+        for epoch in 1..=self.config.training.num_epochs {
+            let mut energy = 0.0;
+            let num_batches = self.for_each_batch(|model, batch| {
+                model.prepare(batch);
+                model.relax();
+                energy += model.graph.energy();
+                model.graph.learn();
+            });
+            let energy = energy / num_batches as f64;
+            println!("epoch {:>4}  energy {:.6}", epoch, energy);
+        }
+    }
+
+    fn eval(&mut self) {
+        self.graph.eval();
+        let (mut energy, mut error, mut count) = (0.0, 0.0, 0);
+        let num_batches = self.for_each_batch(|model, batch| {
+            model.prepare(batch);
+            model.relax();
+            energy += model.graph.energy();
+
+            for (node, targets) in model.labels(batch) {
+                let diff = &model.graph.get_node(node).unwrap().data - &targets;
+                error += (&diff * &diff).data.iter().flatten().sum::<f64>();
+                count += diff.rows * diff.cols;
+            }
+        });
+
+        println!("energy {:.6}", energy / num_batches as f64);
+        if count > 0 {
+            println!("label mse {:.6}", error / count as f64);
+        }
+    }
+
+    // One row per sample with the data of every node that is not observed.
+    fn infer(&mut self, writer: &mut impl Write) -> Result<(), String> {
+        self.graph.eval();
+        let nodes: Vec<(usize, &str, usize)> = self
+            .config
+            .nodes
+            .iter()
+            .filter(|node| !node.observed)
+            .map(|node| (self.handles[&node.name], node.name.as_str(), node.size))
+            .collect();
+        if nodes.is_empty() {
+            return Err("no latent nodes to infer".to_string());
+        }
+        let header: Vec<String> = nodes
+            .iter()
+            .flat_map(|&(_, name, size)| (0..size).map(move |i| format!("{}.{}", name, i)))
+            .collect();
+        writeln!(writer, "{}", header.join(",")).map_err(|error| error.to_string())?;
+        let nodes: Vec<usize> = nodes.into_iter().map(|(index, _, _)| index).collect();
+
+        let mut result = Ok(());
+        self.for_each_batch(|model, batch| {
+            if result.is_err() {
+                return;
+            }
+            model.prepare(batch);
+            model.relax();
+            let data: Vec<&Matrix> = nodes
+                .iter()
+                .map(|&node| &model.graph.get_node(node).unwrap().data)
+                .collect();
+            let rows = (0..data[0].rows)
+                .map(|row| data.iter().flat_map(|m| m.data[row].clone()).collect())
+                .collect();
+            result = csv::write(&Matrix::new(rows), writer);
+        });
+        result.map_err(|error| error.to_string())
+    }
+
+    fn inspect(&self) {
+        println!("nodes");
+        for (i, node) in self.config.nodes.iter().enumerate() {
+            let kind = if node.observed {
+                "observed"
+            } else if node.label {
+                "label"
+            } else {
+                "latent"
+            };
+            let prior = match node.prior.as_ref() {
+                Some(prior) if prior.learnable => "  learnable prior",
+                Some(_) => "  fixed prior",
+                None => "",
+            };
+            println!(
+                "  {:>3}  {:<16} {:>6}  {}{}",
+                i, node.name, node.size, kind, prior
+            );
+        }
+
+        println!("edges");
+        for (i, (config, edge)) in self
+            .config
+            .edges
+            .iter()
+            .zip(self.graph.get_edges())
+            .enumerate()
+        {
+            let arrow = format!("{} -> {}", config.source, config.target);
+            println!(
+                "  {:>3}  {:<32} {:<16} {:>8} parameters",
+                i,
+                arrow,
+                format!("{:?}", config.activation),
+                Function::<GaussianVariable>::num_parameters(&edge.function)
+            );
+        }
+
+        if let Ok(layers) = self.graph.layers() {
+            let layers: Vec<String> = layers
+                .iter()
+                .map(|layer| {
+                    let names: Vec<&str> = layer
+                        .iter()
+                        .map(|&node| self.handles.name(node).unwrap())
+                        .collect();
+                    format!("[{}]", names.join(", "))
+                })
+                .collect();
+            println!("layers {}", layers.join(" "));
+        }
+
+        println!("parameters");
+        for (name, tensor) in self.graph.parameters() {
+            let values = tensor.flatten();
+            let mean = values.iter().sum::<f64>() / values.len().max(1) as f64;
+            println!("  {:<24} {:>8}  mean {:.6}", name, values.len(), mean);
+        }
+        println!("total {}", self.graph.num_parameters());
+    }
+
+    // Runs `step` on each batch of one epoch, holding a field per observed and label
+    // node, and returns the number of batches. Without a dataset there is a single
+    // batch and nodes keep their configured data.
+    fn for_each_batch(&mut self, mut step: impl FnMut(&mut Self, Option<&[Matrix]>)) -> usize {
+        // The loader is taken out so `step` can borrow the model mutably.
+        let mut loader = self.loader.take();
+        let num_batches = match loader.as_mut() {
+            Some(loader) => {
+                let mut num_batches = 0;
+                for batch in loader.epoch() {
+                    step(self, Some(&batch));
+                    num_batches += 1;
+                }
+                num_batches
+            }
+            None => {
+                step(self, None);
+                1
+            }
+        };
+        self.loader = loader;
+        num_batches
+    }
+
+    // Fills observed nodes from the batch, and labels too while training, then
+    // resamples the latents. Labels start at zero in evaluation and are then
+    // predicted by `forward_init` where the graph allows. Nodes with a constant
+    // `value` are resized when a batch, such as the last of an epoch, is smaller.
+    fn prepare(&mut self, batch: Option<&[Matrix]>) {
+        let training = self.graph.mode() == Mode::Train;
+        let rows = match batch {
+            Some(fields) => fields[0].rows,
+            None => self.config.training.batch_size,
+        };
+
+        let mut fields = batch.into_iter().flatten();
+        let mut clamped = Vec::new();
+        for node in self.config.nodes.iter() {
+            let index = self.handles[&node.name];
+            let data = if node.observed || node.label {
+                match fields.next() {
+                    Some(_) if node.label && !training => Some(Matrix::zeros(rows, node.size)),
+                    Some(field) => Some(field.clone()),
+                    None => None,
+                }
+            } else if let Some(value) = node.value {
+                Some(Matrix::ones(rows, node.size) * value)
+            } else {
+                let rng = self.graph.rng();
+                Some(Matrix::normal_with_rng(rows, node.size, 0.0, 0.05, rng))
+            };
+            if let Some(data) = data {
+                self.graph.get_node_mut(index).unwrap().set_data(data);
+            }
+            if self.graph.get_node(index).unwrap().fixed() {
+                clamped.push(index);
+            }
+        }
+
+        if !training {
+            self.graph.forward_init(&clamped);
+        }
+    }
+
+    fn relax(&mut self) {
+        for _ in 0..self.config.training.num_iterations {
+            self.graph.infer();
+        }
+    }
+
+    // Targets of every label node in the batch.
+    fn labels(&self, batch: Option<&[Matrix]>) -> Vec<(usize, Matrix)> {
+        let fields = batch.into_iter().flatten();
+        data_nodes(&self.config)
+            .zip(fields)
+            .filter(|(node, _)| node.label)
+            .map(|(node, field)| (self.handles[&node.name], field.clone()))
+            .collect()
+    }
+}
+
+fn data_nodes(config: &Config) -> impl Iterator<Item = &NodeConfig> {
+    config
+        .nodes
+        .iter()
+        .filter(|node| node.observed || node.label)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn test_options() {
+        let options = Options::parse(&args(&[
+            "--seed",
+            "3",
+            "--learning-rate=0.5",
+            "--data",
+            "x.csv",
+        ]))
+        .unwrap();
+        assert_eq!(options.seed, Some(3));
+        assert_eq!(options.learning_rate, Some(0.5));
+        assert_eq!(options.data.as_deref(), Some("x.csv"));
+
+        let error = |list: &[&str]| Options::parse(&args(list)).err().unwrap();
+        assert_eq!(error(&["--seed"]), "missing value for `--seed`");
+        assert_eq!(
+            error(&["--epochs", "-1"]),
+            "invalid value `-1` for `--epochs`"
+        );
+        assert_eq!(error(&["--colour", "red"]), "unknown option `--colour`");
+        assert_eq!(error(&["train"]), "unexpected argument `train`");
+    }
+
+    #[test]
+    fn test_ragged_batches() {
+        // A constant latent feeding a node read from five rows in batches of two.
+        let config = Config::parse(
+            r#"
+            [training]
+            batch_size = 2
+            num_epochs = 2
+            num_iterations = 3
+
+            [[node]]
+            name = "bias"
+            size = 1
+            value = 1.0
+
+            [[node]]
+            name = "x"
+            size = 2
+            observed = true
+
+            [[node]]
+            name = "y"
+            size = 1
+            label = true
+
+            [[edge]]
+            source = "bias"
+            target = "x"
+
+            [[edge]]
+            source = "x"
+            target = "y"
+            "#,
+        )
+        .unwrap();
+        let name = format!("pc-rs-ragged-{}.csv", std::process::id());
+        let path = std::env::temp_dir().join(name);
+        std::fs::write(&path, "x.0,x.1,y.0\n1,2,3\n4,5,6\n7,8,9\n1,3,5\n2,4,6\n").unwrap();
+
+        let model = Model::new(config, path.to_str(), true);
+        std::fs::remove_file(&path).unwrap();
+        let mut model = model.unwrap();
+        model.train();
+        model.eval();
+        let mut output = Vec::new();
+        model.infer(&mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(output.lines().count(), 1 + 5);
+    }
+
+    #[test]
+    fn test_constant_reset() {
+        let config = Config::parse(
+            r#"
+            [training]
+            batch_size = 2
+            num_iterations = 3
+
+            [[node]]
+            name = "bias"
+            size = 1
+            value = 1.0
+
+            [[node]]
+            name = "x"
+            size = 2
+            observed = true
+
+            [[edge]]
+            source = "bias"
+            target = "x"
+            "#,
+        )
+        .unwrap();
+        let name = format!("pc-rs-constant-{}.csv", std::process::id());
+        let path = std::env::temp_dir().join(name);
+        std::fs::write(&path, "x.0,x.1\n1,2\n4,5\n7,8\n1,3\n").unwrap();
+
+        let model = Model::new(config, path.to_str(), false);
+        std::fs::remove_file(&path).unwrap();
+        let mut model = model.unwrap();
+        let bias = model.handles["bias"];
+        model.graph.train();
+        let num_batches = model.for_each_batch(|model, batch| {
+            model.prepare(batch);
+            assert_eq!(model.graph.get_node(bias).unwrap().data, Matrix::ones(2, 1));
+            model.relax();
+            assert_ne!(model.graph.get_node(bias).unwrap().data, Matrix::ones(2, 1));
+        });
+        assert_eq!(num_batches, 2);
+    }
+
+    #[test]
+    fn test_infer_without_latents() {
+        let config = Config::parse("[[node]]\nname = \"x\"\nsize = 2\nobserved = true").unwrap();
+        let mut model = Model::new(config, None, false).unwrap();
+        let mut output = Vec::new();
+        assert_eq!(
+            model.infer(&mut output),
+            Err("no latent nodes to infer".to_string())
+        );
+        assert!(output.is_empty());
+    }
+}
@@ -0,0 +1,787 @@
+pub mod toml;
+
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use crate::gaussian::{
+    builder::{default_init, initial_data, Handles},
+    function::{GaussianFunction, Placement},
+    prior::GaussianPrior,
+    variable::GaussianVariable,
+};
+use crate::graph::Graph;
+use crate::linalg::{
+    init::{FanMode, Init},
+    math::Activation,
+    matrix::Matrix,
+    vector::Vector,
+};
+
+use self::toml::{Entry, ParseError, Table, Value};
+
+// A Gaussian model described in TOML:
+//
+//     seed = 0
+//
+//     [training]
+//     batch_size = 64
+//     num_epochs = 10
+//     num_iterations = 20
+//     learning_rate = 0.001
+//     inference_rate = 0.001
+//
+//     [[node]]
+//     name = "mu"
+//     size = 10
+//     prior = "learnable"
+//
+//     [[node]]
+//     name = "data"
+//     size = 128
+//     observed = true
+//     value = 2.0
+//
+//     [[edge]]
+//     source = "mu"
+//     target = "data"
+//     activation = "linear"
+//     init = "normal"
+//     std = 0.05
+//
+// Everything except node names and sizes and edge endpoints has a default. Nodes
+// marked `label = true` are clamped in training and inferred in evaluation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Config {
+    pub seed: u64,
+    pub training: TrainingConfig,
+    pub nodes: Vec<NodeConfig>,
+    pub edges: Vec<EdgeConfig>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TrainingConfig {
+    pub batch_size: usize,
+    pub num_epochs: usize,
+    pub num_iterations: usize,
+    pub learning_rate: f64,
+    pub inference_rate: f64,
+}
+
+impl Default for TrainingConfig {
+    fn default() -> Self {
+        Self {
+            batch_size: 64,
+            num_epochs: 10,
+            num_iterations: 20,
+            learning_rate: 0.001,
+            inference_rate: 0.001,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct NodeConfig {
+    pub name: String,
+    pub size: usize,
+    pub observed: bool,
+    pub label: bool,
+    // Constant the node's data is filled with, otherwise latents are sampled and
+    // observed nodes start at zero.
+    pub value: Option<f64>,
+    pub prior: Option<PriorConfig>,
+    pub line: usize,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PriorConfig {
+    pub learnable: bool,
+    pub mean: f64,
+    pub precision: f64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct EdgeConfig {
+    pub source: String,
+    pub target: String,
+    pub activation: Activation,
+    pub init: Init,
+    pub placement: Placement,
+    pub line: usize,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ConfigError {
+    Io(String),
+    Parse(ParseError),
+    // `entry` names the offending table or key, e.g. `edge[1].activation`.
+    Invalid {
+        line: usize,
+        entry: String,
+        message: String,
+    },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io(message) => write!(f, "{}", message),
+            ConfigError::Parse(error) => write!(f, "{}", error),
+            ConfigError::Invalid {
+                line,
+                entry,
+                message,
+            } => write!(f, "line {}: `{}`: {}", line, entry, message),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl From<ParseError> for ConfigError {
+    fn from(error: ParseError) -> Self {
+        ConfigError::Parse(error)
+    }
+}
+
+impl Config {
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        let path = path.as_ref();
+        let source = fs::read_to_string(path)
+            .map_err(|error| ConfigError::Io(format!("{}: {}", path.display(), error)))?;
+        Self::parse(&source)
+    }
+
+    pub fn parse(source: &str) -> Result<Self, ConfigError> {
+        let mut config = Config {
+            seed: 0,
+            training: TrainingConfig::default(),
+            nodes: Vec::new(),
+            edges: Vec::new(),
+        };
+
+        for table in toml::parse(source)? {
+            match (table.name.as_str(), table.array) {
+                ("", false) => {
+                    let mut fields = Fields::new(&table, String::new());
+                    if let Some(seed) = fields.usize("seed")? {
+                        config.seed = seed as u64;
+                    }
+                    fields.finish()?;
+                }
+                ("training", false) => config.training = training(&table)?,
+                ("node", true) => {
+                    let label = format!("node[{}]", config.nodes.len());
+                    config.nodes.push(node(&table, label)?);
+                }
+                ("edge", true) => {
+                    let label = format!("edge[{}]", config.edges.len());
+                    config.edges.push(edge(&table, label)?);
+                }
+                _ => {
+                    return Err(ConfigError::Invalid {
+                        line: table.line,
+                        entry: table.name.clone(),
+                        message: "unknown table".to_string(),
+                    })
+                }
+            }
+        }
+
+        config.validate()?;
+        Ok(config)
+    }
+
+    fn validate(&self) -> Result<(), ConfigError> {
+        for (i, node) in self.nodes.iter().enumerate() {
+            if self.nodes[..i].iter().any(|other| other.name == node.name) {
+                let message = format!("duplicate node `{}`", node.name);
+                return Err(invalid(node.line, format!("node[{}].name", i), message));
+            }
+        }
+
+        for (i, edge) in self.edges.iter().enumerate() {
+            for (key, name) in [("source", &edge.source), ("target", &edge.target)].iter() {
+                if self.node_index(name).is_none() {
+                    let message = format!("unknown node `{}`", name);
+                    return Err(invalid(edge.line, format!("edge[{}].{}", i, key), message));
+                }
+            }
+        }
+
+        // Report the last edge, in file order, whose target leads back to its source.
+        let cycle = self.edges.iter().enumerate().rev().find(|(_, edge)| {
+            let (source, target) = self.endpoints(edge);
+            self.reaches(target, source)
+        });
+        if let Some((i, edge)) = cycle {
+            let message = format!(
+                "edge from `{}` to `{}` closes a cycle",
+                edge.source, edge.target
+            );
+            return Err(invalid(edge.line, format!("edge[{}]", i), message));
+        }
+
+        Ok(())
+    }
+
+    pub fn node_index(&self, name: &str) -> Option<usize> {
+        self.nodes.iter().position(|node| node.name == name)
+    }
+
+    fn reaches(&self, from: usize, to: usize) -> bool {
+        let mut visited = vec![false; self.nodes.len()];
+        let mut stack = vec![from];
+        while let Some(node) = stack.pop() {
+            if node == to {
+                return true;
+            }
+            if !visited[node] {
+                visited[node] = true;
+                for edge in self.edges.iter() {
+                    let (source, target) = self.endpoints(edge);
+                    if source == node {
+                        stack.push(target);
+                    }
+                }
+            }
+        }
+        false
+    }
+
+    fn endpoints(&self, edge: &EdgeConfig) -> (usize, usize) {
+        (
+            self.node_index(&edge.source).unwrap(),
+            self.node_index(&edge.target).unwrap(),
+        )
+    }
+
+    // Node `i` of the graph is `nodes[i]` and edge `i` is `edges[i]`.
+    pub fn build(&self) -> (Graph<GaussianVariable, GaussianFunction>, Handles) {
+        let mut graph = Graph::with_seed(self.seed);
+        let mut handles = Handles::default();
+        let batch_size = self.training.batch_size;
+        graph.set_inference_rate(self.training.inference_rate);
+
+        for node in self.nodes.iter() {
+            let data = match node.value {
+                Some(value) => Matrix::ones(batch_size, node.size) * value,
+                None => initial_data(batch_size, node.size, node.observed, graph.rng()),
+            };
+            let mut variable = GaussianVariable::new(data, node.observed);
+            if let Some(prior) = node.prior.as_ref() {
+                let mean = Vector::new(vec![prior.mean; node.size]);
+                let precision = Vector::new(vec![prior.precision; node.size]);
+                variable = variable.with_prior(if prior.learnable {
+                    GaussianPrior::learnable(mean, precision)
+                } else {
+                    GaussianPrior::fixed(mean, precision)
+                });
+            }
+            let index = graph.add_node(variable);
+            handles.insert(&node.name, index);
+        }
+
+        for edge in self.edges.iter() {
+            let (source, target) = self.endpoints(edge);
+            let function = GaussianFunction::from_init_with_rng(
+                self.nodes[source].size,
+                self.nodes[target].size,
+                edge.init,
+                edge.activation,
+                graph.rng(),
+            )
+            .with_placement(edge.placement)
+            .with_learning_rate(self.training.learning_rate);
+            graph.add_edge(source, target, function);
+        }
+
+        let labels: Vec<usize> = self
+            .nodes
+            .iter()
+            .filter(|node| node.label)
+            .map(|node| handles[&node.name])
+            .collect();
+        graph.set_labels(&labels);
+        (graph, handles)
+    }
+}
+
+fn training(table: &Table) -> Result<TrainingConfig, ConfigError> {
+    let mut fields = Fields::new(table, "training".to_string());
+    let mut training = TrainingConfig::default();
+    if let Some(batch_size) = fields.positive("batch_size")? {
+        training.batch_size = batch_size;
+    }
+    if let Some(num_epochs) = fields.usize("num_epochs")? {
+        training.num_epochs = num_epochs;
+    }
+    if let Some(num_iterations) = fields.usize("num_iterations")? {
+        training.num_iterations = num_iterations;
+    }
+    if let Some(learning_rate) = fields.f64("learning_rate")? {
+        training.learning_rate = learning_rate;
+    }
+    if let Some(inference_rate) = fields.f64("inference_rate")? {
+        training.inference_rate = inference_rate;
+    }
+    fields.finish()?;
+    Ok(training)
+}
+
+fn node(table: &Table, label: String) -> Result<NodeConfig, ConfigError> {
+    let mut fields = Fields::new(table, label);
+    let name = fields.required("name", Fields::string)?;
+    let size = fields.required("size", Fields::positive)?;
+    let observed = fields.bool("observed")?.unwrap_or(false);
+    let label = fields.bool("label")?.unwrap_or(false);
+    if observed && label {
+        let message = "a label cannot also be observed".to_string();
+        return Err(fields.error("label", message));
+    }
+    let value = fields.f64("value")?;
+
+    let learnable = match fields.string("prior")? {
+        Some(kind) if kind == "fixed" => Some(false),
+        Some(kind) if kind == "learnable" => Some(true),
+        Some(kind) => {
+            let message = format!("unknown prior `{}`, expected `fixed` or `learnable`", kind);
+            return Err(fields.error("prior", message));
+        }
+        None => None,
+    };
+    let mean = fields.f64("prior_mean")?.unwrap_or(0.0);
+    let precision = fields.f64("prior_precision")?.unwrap_or(1.0);
+    if precision <= 0.0 {
+        return Err(fields.error("prior_precision", "must be positive".to_string()));
+    }
+    let prior = learnable.map(|learnable| PriorConfig {
+        learnable,
+        mean,
+        precision,
+    });
+
+    fields.finish()?;
+    Ok(NodeConfig {
+        name,
+        size,
+        observed,
+        label,
+        value,
+        prior,
+        line: table.line,
+    })
+}
+
+fn edge(table: &Table, label: String) -> Result<EdgeConfig, ConfigError> {
+    let mut fields = Fields::new(table, label);
+    let source = fields.required("source", Fields::string)?;
+    let target = fields.required("target", Fields::string)?;
+
+    let activation = match fields.string("activation")? {
+        Some(name) => activation(&mut fields, &name)?,
+        None => Activation::Linear,
+    };
+
+    let init = match fields.string("init")? {
+        Some(name) => init(&mut fields, &name)?,
+        None => default_init(activation),
+    };
+
+    let placement = match fields.string("placement")?.as_deref() {
+        Some("source") => Placement::Source,
+        Some("product") | None => Placement::Product,
+        Some("output") => Placement::Output,
+        Some(name) => return Err(unknown(&fields, "placement", name)),
+    };
+
+    fields.finish()?;
+    Ok(EdgeConfig {
+        source,
+        target,
+        activation,
+        init,
+        placement,
+        line: table.line,
+    })
+}
+
+// Reads the activation `name` together with `alpha` for `leaky_relu` and `elu`.
+fn activation(fields: &mut Fields, name: &str) -> Result<Activation, ConfigError> {
+    let activation = match name {
+        "linear" => Activation::Linear,
+        "relu" => Activation::ReLU,
+        "tanh" => Activation::Tanh,
+        "sigmoid" => Activation::Sigmoid,
+        "leaky_relu" => Activation::LeakyReLU(fields.f64("alpha")?.unwrap_or(0.01)),
+        "elu" => Activation::ELU(fields.f64("alpha")?.unwrap_or(1.0)),
+        "gelu" => Activation::GELU,
+        "softplus" => Activation::Softplus,
+        "silu" => Activation::SiLU,
+        "softmax" => Activation::Softmax,
+        _ => return Err(unknown(fields, "activation", name)),
+    };
+    Ok(activation)
+}
+
+// Reads the initialiser `name` together with its settings: `mean` and `std` for the
+// normal ones, `low` and `high` for `uniform`, `sparsity` and `std` for `sparse`,
+// `gain` for `orthogonal` and `fan_mode` (`fan_in` or `fan_out`) for the Kaiming ones.
+// Settings of other initialisers are left unread, so `Fields::finish` rejects them.
+fn init(fields: &mut Fields, name: &str) -> Result<Init, ConfigError> {
+    let init = match name {
+        "zeros" => Init::Zeros,
+        "ones" => Init::Ones,
+        "uniform" => {
+            let low = fields.f64("low")?.unwrap_or(-0.05);
+            let high = fields.f64("high")?.unwrap_or(0.05);
+            if low >= high {
+                return Err(fields.error("high", "must be greater than `low`".to_string()));
+            }
+            Init::Uniform { low, high }
+        }
+        "normal" | "truncated_normal" => {
+            let mean = fields.f64("mean")?.unwrap_or(0.0);
+            let std = fields.f64("std")?.unwrap_or(0.05);
+            if name == "normal" {
+                Init::Normal { mean, std }
+            } else {
+                Init::TruncatedNormal { mean, std }
+            }
+        }
+        "xavier_uniform" => Init::XavierUniform,
+        "xavier_normal" => Init::XavierNormal,
+        "kaiming_uniform" => Init::KaimingUniform(fan_mode(fields)?),
+        "kaiming_normal" => Init::KaimingNormal(fan_mode(fields)?),
+        "lecun_uniform" => Init::LecunUniform,
+        "lecun_normal" => Init::LecunNormal,
+        "orthogonal" => Init::Orthogonal {
+            gain: fields.f64("gain")?.unwrap_or(1.0),
+        },
+        "sparse" => {
+            let sparsity = fields.f64("sparsity")?.unwrap_or(0.1);
+            if !(0.0..=1.0).contains(&sparsity) {
+                return Err(fields.error("sparsity", "must be between 0 and 1".to_string()));
+            }
+            let std = fields.f64("std")?.unwrap_or(0.05);
+            Init::Sparse { sparsity, std }
+        }
+        _ => return Err(unknown(fields, "init", name)),
+    };
+    Ok(init)
+}
+
+fn fan_mode(fields: &mut Fields) -> Result<FanMode, ConfigError> {
+    match fields.string("fan_mode")?.as_deref() {
+        Some("fan_in") | None => Ok(FanMode::FanIn),
+        Some("fan_out") => Ok(FanMode::FanOut),
+        Some(mode) => Err(unknown(fields, "fan_mode", mode)),
+    }
+}
+
+fn invalid(line: usize, entry: String, message: String) -> ConfigError {
+    ConfigError::Invalid {
+        line,
+        entry,
+        message,
+    }
+}
+
+fn unknown(fields: &Fields, key: &str, name: &str) -> ConfigError {
+    fields.error(key, format!("unknown {} `{}`", key.replace('_', " "), name))
+}
+
+// Typed access to the entries of a table. Every key read is recorded so `finish`
+// can reject the ones left over.
+struct Fields<'a> {
+    table: &'a Table,
+    label: String,
+    used: Vec<&'a str>,
+}
+
+impl<'a> Fields<'a> {
+    fn new(table: &'a Table, label: String) -> Self {
+        Self {
+            table,
+            label,
+            used: Vec::new(),
+        }
+    }
+
+    fn entry_name(&self, key: &str) -> String {
+        if self.label.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}.{}", self.label, key)
+        }
+    }
+
+    fn error(&self, key: &str, message: String) -> ConfigError {
+        let line = self.entry(key).map_or(self.table.line, |entry| entry.line);
+        invalid(line, self.entry_name(key), message)
+    }
+
+    fn entry(&self, key: &str) -> Option<&'a Entry> {
+        self.table.entries.iter().find(|entry| entry.key == key)
+    }
+
+    fn get(&mut self, key: &'a str) -> Option<&'a Value> {
+        self.used.push(key);
+        self.entry(key).map(|entry| &entry.value)
+    }
+
+    fn mismatch(&self, key: &str, expected: &str, value: &Value) -> ConfigError {
+        let message = format!("expected {}, found {}", expected, value.type_str());
+        self.error(key, message)
+    }
+
+    fn required<T>(
+        &mut self,
+        key: &'a str,
+        read: fn(&mut Self, &'a str) -> Result<Option<T>, ConfigError>,
+    ) -> Result<T, ConfigError> {
+        read(self, key)?.ok_or_else(|| self.error(key, "missing required key".to_string()))
+    }
+
+    fn string(&mut self, key: &'a str) -> Result<Option<String>, ConfigError> {
+        match self.get(key) {
+            Some(Value::String(value)) => Ok(Some(value.clone())),
+            Some(value) => Err(self.mismatch(key, "string", value)),
+            None => Ok(None),
+        }
+    }
+
+    fn bool(&mut self, key: &'a str) -> Result<Option<bool>, ConfigError> {
+        match self.get(key) {
+            Some(Value::Boolean(value)) => Ok(Some(*value)),
+            Some(value) => Err(self.mismatch(key, "boolean", value)),
+            None => Ok(None),
+        }
+    }
+
+    fn usize(&mut self, key: &'a str) -> Result<Option<usize>, ConfigError> {
+        match self.get(key) {
+            Some(Value::Integer(value)) if *value >= 0 => Ok(Some(*value as usize)),
+            Some(Value::Integer(_)) => Err(self.error(key, "must not be negative".to_string())),
+            Some(value) => Err(self.mismatch(key, "integer", value)),
+            None => Ok(None),
+        }
+    }
+
+    fn positive(&mut self, key: &'a str) -> Result<Option<usize>, ConfigError> {
+        match self.usize(key)? {
+            Some(0) => Err(self.error(key, "must be positive".to_string())),
+            value => Ok(value),
+        }
+    }
+
+    fn f64(&mut self, key: &'a str) -> Result<Option<f64>, ConfigError> {
+        match self.get(key) {
+            Some(Value::Float(value)) => Ok(Some(*value)),
+            Some(Value::Integer(value)) => Ok(Some(*value as f64)),
+            Some(value) => Err(self.mismatch(key, "number", value)),
+            None => Ok(None),
+        }
+    }
+
+    fn finish(self) -> Result<(), ConfigError> {
+        match self
+            .table
+            .entries
+            .iter()
+            .find(|entry| !self.used.contains(&entry.key.as_str()))
+        {
+            Some(entry) => Err(self.error(&entry.key, "unknown key".to_string())),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::Variable;
+
+    const SOURCE: &str = r#"
+seed = 7
+
+[training]
+batch_size = 4
+num_iterations = 5
+learning_rate = 0.01
+
+[[node]]
+name = "mu"
+size = 3
+prior = "learnable"
+prior_precision = 2
+
+[[node]]
+name = "data"
+size = 5
+observed = true
+value = 2.0
+
+[[edge]]
+source = "mu"
+target = "data"
+activation = "leaky_relu"
+alpha = 0.2
+init = "zeros"
+placement = "output"
+"#;
+
+    fn error(source: &str) -> String {
+        Config::parse(source).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn test_parse_and_build() {
+        let config = Config::parse(SOURCE).unwrap();
+        assert_eq!(config.seed, 7);
+        assert_eq!(config.training.batch_size, 4);
+        assert_eq!(config.training.num_epochs, 10);
+        assert_eq!(config.nodes[1].line, 15);
+        assert_eq!(config.edges[0].activation, Activation::LeakyReLU(0.2));
+        assert_eq!(config.edges[0].placement, Placement::Output);
+
+        let (graph, handles) = config.build();
+        assert_eq!(graph.inference_rate(), 0.001);
+        let (mu, data) = (handles["mu"], handles["data"]);
+        let node = graph.get_node(data).unwrap();
+        assert!(node.fixed());
+        assert_eq!(node.data, Matrix::ones(4, 5) * 2.0);
+        let prior = graph.get_node(mu).unwrap().prior().unwrap();
+        assert_eq!(prior.precision(), Vector::new(vec![2.0; 3]));
+
+        let edge = graph.get_edges().next().unwrap();
+        assert_eq!((edge.source, edge.target), (mu, data));
+        assert_eq!(edge.function.weights(), &Matrix::zeros(3, 5));
+        assert_eq!(edge.function.learning_rate(), 0.01);
+    }
+
+    #[test]
+    fn test_errors_point_at_entries() {
+        assert_eq!(
+            error(&SOURCE.replace("\"leaky_relu\"", "\"rlu\"")),
+            "line 24: `edge[0].activation`: unknown activation `rlu`"
+        );
+        assert_eq!(
+            error(&SOURCE.replace("\"leaky_relu\"", "\"relu\"")),
+            "line 25: `edge[0].alpha`: unknown key"
+        );
+        assert_eq!(
+            error(&SOURCE.replace("size = 5", "size = \"5\"")),
+            "line 17: `node[1].size`: expected integer, found string"
+        );
+        assert_eq!(
+            error(&SOURCE.replace("size = 5", "size = 0")),
+            "line 17: `node[1].size`: must be positive"
+        );
+        assert_eq!(
+            error(&SOURCE.replace("size = 5", "sise = 5")),
+            "line 15: `node[1].size`: missing required key"
+        );
+        assert_eq!(
+            error(&SOURCE.replace("value = 2.0", "value = 2.0\ncolour = 1")),
+            "line 20: `node[1].colour`: unknown key"
+        );
+        assert_eq!(
+            error(&SOURCE.replace("target = \"data\"", "target = \"dat\"")),
+            "line 21: `edge[0].target`: unknown node `dat`"
+        );
+        assert_eq!(
+            error(&SOURCE.replace("\"data\"", "\"mu\"")),
+            "line 15: `node[1].name`: duplicate node `mu`"
+        );
+        assert_eq!(
+            error(&SOURCE.replace("[training]", "[trianing]")),
+            "line 4: `trianing`: unknown table"
+        );
+        assert_eq!(error("seed = -1"), "line 1: `seed`: must not be negative");
+        assert_eq!(error("seed = 1\nseed = 2"), "line 2: duplicate key");
+        assert_eq!(
+            error(&SOURCE.replace("observed = true", "observed = true\nlabel = true")),
+            "line 19: `node[1].label`: a label cannot also be observed"
+        );
+    }
+
+    #[test]
+    fn test_inits() {
+        let init = |keys: &str| {
+            let source = SOURCE.replace("init = \"zeros\"", keys);
+            Config::parse(&source).map(|config| config.edges[0].init)
+        };
+        assert_eq!(
+            init("init = \"uniform\"\nlow = -1\nhigh = 0.5"),
+            Ok(Init::Uniform {
+                low: -1.0,
+                high: 0.5
+            })
+        );
+        assert_eq!(
+            init("init = \"sparse\"\nsparsity = 0.5\nstd = 0.1"),
+            Ok(Init::Sparse {
+                sparsity: 0.5,
+                std: 0.1
+            })
+        );
+        assert_eq!(
+            init("init = \"kaiming_normal\"\nfan_mode = \"fan_out\""),
+            Ok(Init::KaimingNormal(FanMode::FanOut))
+        );
+        assert_eq!(
+            init("init = \"kaiming_uniform\""),
+            Ok(Init::KaimingUniform(FanMode::FanIn))
+        );
+
+        let error = |keys: &str| error(&SOURCE.replace("init = \"zeros\"", keys));
+        assert_eq!(
+            error("init = \"uniform\"\nlow = 1\nhigh = 0"),
+            "line 28: `edge[0].high`: must be greater than `low`"
+        );
+        assert_eq!(
+            error("init = \"sparse\"\nsparsity = 2"),
+            "line 27: `edge[0].sparsity`: must be between 0 and 1"
+        );
+        assert_eq!(
+            error("init = \"kaiming_normal\"\nfan_mode = \"fan\""),
+            "line 27: `edge[0].fan_mode`: unknown fan mode `fan`"
+        );
+        assert_eq!(
+            error("init = \"xavier_uniform\"\nstd = 5.0"),
+            "line 27: `edge[0].std`: unknown key"
+        );
+        assert_eq!(
+            error("init = \"normal\"\nlow = 1\nhigh = 0"),
+            "line 27: `edge[0].low`: unknown key"
+        );
+    }
+
+    #[test]
+    fn test_toml_syntax() {
+        let source = SOURCE
+            .replace("[training]\nbatch_size = 4", "training.batch_size = 4")
+            .replace("num_iterations = 5", "training.num_iterations = 5")
+            .replace("learning_rate = 0.01", "training.learning_rate = 0.01")
+            .replace("prior = \"learnable\"", "prior = 'learnable'");
+        let source = format!(
+            "{}\n[[edge]]\nsource = \"mu\"\ntarget = \"extra\"\n{}",
+            source, "[[node]]\nname = \"extra\"\nsize = 2\n"
+        );
+        let config = Config::parse(&source).unwrap();
+        assert_eq!(config.training.batch_size, 4);
+        assert!(config.nodes[0].prior.as_ref().unwrap().learnable);
+        assert_eq!(config.nodes[2].name, "extra");
+        assert_eq!(config.edges[1].target, "extra");
+    }
+
+    #[test]
+    fn test_cycle() {
+        let source = format!("{}\n[[edge]]\nsource = \"data\"\ntarget = \"mu\"\n", SOURCE);
+        assert_eq!(
+            error(&source),
+            "line 29: `edge[1]`: edge from `data` to `mu` closes a cycle"
+        );
+    }
+}
@@ -0,0 +1,222 @@
+use std::fmt;
+
+use ::toml::de::{DeTable, DeValue};
+use ::toml::map::Map;
+use ::toml::Spanned;
+
+pub use ::toml::Value;
+
+// Model configs are parsed with the `toml` crate, then split into the tables a config
+// is read from: the root, one per `[name]` table and one per element of a `[[name]]`
+// array. Every entry keeps its line for error reporting.
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Entry {
+    pub key: String,
+    pub value: Value,
+    pub line: usize,
+}
+
+// A table and its entries in file order. The root table has an empty name and line
+// 0; each element of an array of tables has `array` set.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Table {
+    pub name: String,
+    pub array: bool,
+    pub line: usize,
+    pub entries: Vec<Entry>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+pub fn parse(source: &str) -> Result<Vec<Table>, ParseError> {
+    let document = DeTable::parse(source).map_err(|error| ParseError {
+        line: error.span().map_or(0, |span| line(source, span.start)),
+        message: error.message().to_string(),
+    })?;
+
+    let mut root = Table {
+        name: String::new(),
+        array: false,
+        line: 0,
+        entries: Vec::new(),
+    };
+    let mut tables = Vec::new();
+    for (key, value) in document.get_ref().iter() {
+        let name = key.get_ref().to_string();
+        match value.get_ref() {
+            DeValue::Table(entries) => {
+                let line = line(source, value.span().start);
+                tables.push(table(source, name, false, line, entries)?);
+            }
+            DeValue::Array(elements) if is_array_of_tables(elements) => {
+                for element in elements.iter() {
+                    if let DeValue::Table(entries) = element.get_ref() {
+                        let line = line(source, element.span().start);
+                        tables.push(table(source, name.clone(), true, line, entries)?);
+                    }
+                }
+            }
+            _ => root.entries.push(Entry {
+                key: name,
+                value: convert(source, value)?,
+                line: line(source, key.span().start),
+            }),
+        }
+    }
+
+    root.entries.sort_by_key(|entry| entry.line);
+    tables.sort_by_key(|table| table.line);
+    tables.insert(0, root);
+    Ok(tables)
+}
+
+fn table(
+    source: &str,
+    name: String,
+    array: bool,
+    line: usize,
+    entries: &DeTable,
+) -> Result<Table, ParseError> {
+    let mut table = Table {
+        name,
+        array,
+        line,
+        entries: Vec::new(),
+    };
+    for (key, value) in entries.iter() {
+        table.entries.push(Entry {
+            key: key.get_ref().to_string(),
+            value: convert(source, value)?,
+            line: self::line(source, key.span().start),
+        });
+    }
+    table.entries.sort_by_key(|entry| entry.line);
+    Ok(table)
+}
+
+fn is_array_of_tables(elements: &[Spanned<DeValue>]) -> bool {
+    !elements.is_empty()
+        && elements
+            .iter()
+            .all(|element| matches!(element.get_ref(), DeValue::Table(_)))
+}
+
+fn convert(source: &str, value: &Spanned<DeValue>) -> Result<Value, ParseError> {
+    let invalid = || ParseError {
+        line: line(source, value.span().start),
+        message: format!("`{}` is out of range", &source[value.span()]),
+    };
+    let value = match value.get_ref() {
+        DeValue::String(string) => Value::String(string.to_string()),
+        DeValue::Integer(integer) => i64::from_str_radix(integer.as_str(), integer.radix())
+            .map(Value::Integer)
+            .map_err(|_| invalid())?,
+        DeValue::Float(float) => float
+            .as_str()
+            .parse()
+            .map(Value::Float)
+            .map_err(|_| invalid())?,
+        DeValue::Boolean(boolean) => Value::Boolean(*boolean),
+        DeValue::Datetime(datetime) => Value::Datetime(*datetime),
+        DeValue::Array(elements) => Value::Array(
+            elements
+                .iter()
+                .map(|element| convert(source, element))
+                .collect::<Result<_, _>>()?,
+        ),
+        DeValue::Table(entries) => {
+            let mut table = Map::new();
+            for (key, value) in entries.iter() {
+                table.insert(key.get_ref().to_string(), convert(source, value)?);
+            }
+            Value::Table(table)
+        }
+    };
+    Ok(value)
+}
+
+fn line(source: &str, offset: usize) -> usize {
+    source[..offset].matches('\n').count() + 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let source = r#"
+            seed = 3 # trailing comment
+            name = 'a # b "c"'
+
+            [training]
+            rate = 1e-3
+            sizes = [
+                1,
+                2_000,
+                -3,
+            ]
+            flags = [true, false]
+
+            [[node]]
+            size = 4.5
+            prior = { mean = 1, precision = 2.0 }
+            [[node]]
+            init.kind = "normal"
+        "#;
+        let tables = parse(source).unwrap();
+        assert_eq!(tables.len(), 4);
+        assert_eq!(tables[0].entries[0].value, Value::Integer(3));
+        assert_eq!(
+            tables[0].entries[1].value,
+            Value::String("a # b \"c\"".to_string())
+        );
+        assert_eq!(tables[1].name, "training");
+        assert_eq!(tables[1].entries[0].value, Value::Float(1e-3));
+        assert_eq!(
+            tables[1].entries[1].value,
+            Value::Array(vec![
+                Value::Integer(1),
+                Value::Integer(2000),
+                Value::Integer(-3)
+            ])
+        );
+        assert_eq!(
+            tables[1].entries[2].value,
+            Value::Array(vec![Value::Boolean(true), Value::Boolean(false)])
+        );
+        assert!(tables[2].array && tables[3].array);
+        assert_eq!((tables[2].line, tables[3].line), (14, 17));
+        assert_eq!(tables[2].entries[0].line, 15);
+        assert_eq!(tables[2].entries[1].value.type_str(), "table");
+        assert_eq!(tables[3].entries[0].key, "init");
+        assert_eq!(tables[3].entries[0].value["kind"].as_str(), Some("normal"));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = |source: &str| parse(source).unwrap_err();
+        assert_eq!(error("a = 1\nb").line, 2);
+        assert_eq!(error("a = 1\na = 2").line, 2);
+        assert_eq!(error("[t]\n[t]").line, 2);
+        assert_eq!(error("a = \"open").line, 1);
+        assert_eq!(error("a = 1\nb = [1 2]").line, 2);
+        assert_eq!(
+            error("a = 99999999999999999999").message,
+            "`99999999999999999999` is out of range"
+        );
+    }
+}
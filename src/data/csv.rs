@@ -0,0 +1,105 @@
+use std::fmt;
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+
+use crate::linalg::matrix::Matrix;
+
+// Comma-separated numbers, one sample per row. Blank lines and lines starting with
+// `#` are skipped, as is a first line that does not parse, which is taken as a header.
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CsvError {
+    Io(String),
+    Invalid { line: usize, message: String },
+}
+
+impl fmt::Display for CsvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CsvError::Io(message) => write!(f, "{}", message),
+            CsvError::Invalid { line, message } => write!(f, "line {}: {}", line, message),
+        }
+    }
+}
+
+impl std::error::Error for CsvError {}
+
+pub fn read(path: impl AsRef<Path>) -> Result<Matrix, CsvError> {
+    let path = path.as_ref();
+    let source = fs::read_to_string(path)
+        .map_err(|error| CsvError::Io(format!("{}: {}", path.display(), error)))?;
+    parse(&source)
+}
+
+pub fn parse(source: &str) -> Result<Matrix, CsvError> {
+    let mut rows: Vec<Vec<f64>> = Vec::new();
+    let mut first = true;
+    for (index, text) in source.lines().enumerate() {
+        let line = index + 1;
+        let text = text.trim();
+        if text.is_empty() || text.starts_with('#') {
+            continue;
+        }
+
+        let row: Result<Vec<f64>, _> = text.split(',').map(|v| v.trim().parse()).collect();
+        let row = match row {
+            Ok(row) => row,
+            Err(_) if first => {
+                first = false;
+                continue;
+            }
+            Err(_) => {
+                let message = format!("invalid row `{}`", text);
+                return Err(CsvError::Invalid { line, message });
+            }
+        };
+        first = false;
+
+        if let Some(cols) = rows.first().map(|first| first.len()) {
+            if row.len() != cols {
+                let message = format!("expected {} columns, found {}", cols, row.len());
+                return Err(CsvError::Invalid { line, message });
+            }
+        }
+        rows.push(row);
+    }
+
+    if rows.is_empty() {
+        return Err(CsvError::Invalid {
+            line: 0,
+            message: "no rows".to_string(),
+        });
+    }
+    Ok(Matrix::new(rows))
+}
+
+pub fn write(matrix: &Matrix, writer: &mut impl Write) -> io::Result<()> {
+    for row in matrix.data.iter() {
+        let row: Vec<String> = row.iter().map(|v| v.to_string()).collect();
+        writeln!(writer, "{}", row.join(","))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roundtrip() {
+        let matrix = Matrix::new(vec![vec![1.5, -2.0], vec![0.1, 3e-8]]);
+        let mut buffer = Vec::new();
+        write(&matrix, &mut buffer).unwrap();
+        let source = format!("a,b\n# comment\n\n{}", String::from_utf8(buffer).unwrap());
+        assert_eq!(parse(&source).unwrap().data, matrix.data);
+    }
+
+    #[test]
+    fn test_errors() {
+        let error = parse("1,2\n3").unwrap_err();
+        assert_eq!(error.to_string(), "line 2: expected 2 columns, found 1");
+        let error = parse("x,y\n1,2\n3,z").unwrap_err();
+        assert_eq!(error.to_string(), "line 3: invalid row `3,z`");
+    }
+}
@@ -0,0 +1,236 @@
+pub mod csv;
+
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+
+use crate::linalg::matrix::Matrix;
+
+// A collection of samples, each made of one row per field, e.g. inputs and labels.
+pub trait Dataset {
+    fn len(&self) -> usize;
+    fn num_fields(&self) -> usize;
+    // Rows `indices` of every field, in order.
+    fn batch(&self, indices: &[usize]) -> Vec<Matrix>;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MatrixDataset {
+    fields: Vec<Matrix>,
+}
+
+impl MatrixDataset {
+    pub fn new(fields: Vec<Matrix>) -> Self {
+        assert!(!fields.is_empty());
+        assert!(fields.iter().all(|field| field.rows == fields[0].rows));
+        Self { fields }
+    }
+
+    // Splits the columns of `data` into consecutive fields of the given sizes.
+    pub fn from_columns(data: &Matrix, sizes: &[usize]) -> Self {
+        assert_eq!(sizes.iter().sum::<usize>(), data.cols);
+        let mut start = 0;
+        let fields = sizes
+            .iter()
+            .map(|&size| {
+                let rows = data
+                    .data
+                    .iter()
+                    .map(|row| row[start..start + size].to_vec())
+                    .collect();
+                start += size;
+                Matrix::new(rows)
+            })
+            .collect();
+        Self::new(fields)
+    }
+
+    pub fn fields(&self) -> &[Matrix] {
+        &self.fields
+    }
+}
+
+impl Dataset for MatrixDataset {
+    fn len(&self) -> usize {
+        self.fields[0].rows
+    }
+
+    fn num_fields(&self) -> usize {
+        self.fields.len()
+    }
+
+    fn batch(&self, indices: &[usize]) -> Vec<Matrix> {
+        self.fields
+            .iter()
+            .map(|field| Matrix::new(indices.iter().map(|&i| field.data[i].clone()).collect()))
+            .collect()
+    }
+}
+
+// What to do with the final batch of an epoch when it is smaller than the batch size.
+// `Pad` fills it up by repeating samples from the start of the epoch, so every batch
+// has the same shape.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Remainder {
+    #[default]
+    Keep,
+    DropLast,
+    Pad,
+}
+
+// Yields mini-batches of a dataset, reshuffled every epoch from its own seeded rng.
+pub struct DataLoader<D: Dataset> {
+    dataset: D,
+    batch_size: usize,
+    shuffle: bool,
+    remainder: Remainder,
+    rng: StdRng,
+}
+
+impl<D: Dataset> DataLoader<D> {
+    pub fn new(dataset: D, batch_size: usize) -> Self {
+        Self::with_rng(dataset, batch_size, StdRng::from_entropy())
+    }
+
+    pub fn with_seed(dataset: D, batch_size: usize, seed: u64) -> Self {
+        Self::with_rng(dataset, batch_size, StdRng::seed_from_u64(seed))
+    }
+
+    pub fn with_rng(dataset: D, batch_size: usize, rng: StdRng) -> Self {
+        assert!(batch_size > 0);
+        Self {
+            dataset,
+            batch_size,
+            shuffle: true,
+            remainder: Remainder::default(),
+            rng,
+        }
+    }
+
+    pub fn with_shuffle(mut self, shuffle: bool) -> Self {
+        self.shuffle = shuffle;
+        self
+    }
+
+    pub fn with_remainder(mut self, remainder: Remainder) -> Self {
+        self.remainder = remainder;
+        self
+    }
+
+    pub fn dataset(&self) -> &D {
+        &self.dataset
+    }
+
+    pub fn batch_size(&self) -> usize {
+        self.batch_size
+    }
+
+    pub fn num_batches(&self) -> usize {
+        let len = self.dataset.len();
+        match self.remainder {
+            Remainder::DropLast => len / self.batch_size,
+            Remainder::Keep | Remainder::Pad => len.div_ceil(self.batch_size),
+        }
+    }
+
+    // Batches for one pass over the dataset.
+    pub fn epoch(&mut self) -> Batches<'_, D> {
+        let mut order: Vec<usize> = (0..self.dataset.len()).collect();
+        if self.shuffle {
+            order.shuffle(&mut self.rng);
+        }
+        Batches {
+            loader: self,
+            order,
+            batch: 0,
+        }
+    }
+}
+
+pub struct Batches<'a, D: Dataset> {
+    loader: &'a DataLoader<D>,
+    order: Vec<usize>,
+    batch: usize,
+}
+
+impl<'a, D: Dataset> Iterator for Batches<'a, D> {
+    type Item = Vec<Matrix>;
+
+    fn next(&mut self) -> Option<Vec<Matrix>> {
+        if self.batch >= self.loader.num_batches() {
+            return None;
+        }
+
+        let batch_size = self.loader.batch_size;
+        let start = self.batch * batch_size;
+        let end = (start + batch_size).min(self.order.len());
+        let mut indices = self.order[start..end].to_vec();
+        if self.loader.remainder == Remainder::Pad {
+            let missing = batch_size - indices.len();
+            indices.extend(self.order.iter().cycle().take(missing));
+        }
+
+        self.batch += 1;
+        Some(self.loader.dataset.batch(&indices))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dataset(len: usize) -> MatrixDataset {
+        let data = Matrix::new((0..len).map(|i| vec![i as f64, -(i as f64)]).collect());
+        MatrixDataset::from_columns(&data, &[1, 1])
+    }
+
+    fn firsts(batch: &[Matrix]) -> Vec<usize> {
+        batch[0].data.iter().map(|row| row[0] as usize).collect()
+    }
+
+    #[test]
+    fn test_from_columns() {
+        let dataset = dataset(3);
+        assert_eq!(dataset.len(), 3);
+        assert_eq!(dataset.num_fields(), 2);
+        let batch = dataset.batch(&[2, 0]);
+        assert_eq!(batch[0], Matrix::new(vec![vec![2.0], vec![0.0]]));
+        assert_eq!(batch[1], Matrix::new(vec![vec![-2.0], vec![-0.0]]));
+    }
+
+    #[test]
+    fn test_remainder() {
+        let mut loader = DataLoader::new(dataset(5), 2).with_shuffle(false);
+        let batches: Vec<_> = loader.epoch().map(|batch| firsts(&batch)).collect();
+        assert_eq!(batches, vec![vec![0, 1], vec![2, 3], vec![4]]);
+
+        let mut loader = loader.with_remainder(Remainder::DropLast);
+        assert_eq!(loader.num_batches(), 2);
+        assert_eq!(loader.epoch().count(), 2);
+
+        let mut loader = loader.with_remainder(Remainder::Pad);
+        let batches: Vec<_> = loader.epoch().map(|batch| firsts(&batch)).collect();
+        assert_eq!(batches, vec![vec![0, 1], vec![2, 3], vec![4, 0]]);
+    }
+
+    #[test]
+    fn test_shuffle_is_seeded() {
+        let epochs = |seed| {
+            let mut loader = DataLoader::with_seed(dataset(10), 4, seed);
+            let first: Vec<usize> = loader.epoch().flat_map(|batch| firsts(&batch)).collect();
+            let second: Vec<usize> = loader.epoch().flat_map(|batch| firsts(&batch)).collect();
+            (first, second)
+        };
+
+        let (first, second) = epochs(0);
+        assert_eq!((first.clone(), second.clone()), epochs(0));
+        assert_ne!(first, second);
+        assert_ne!(first, epochs(1).0);
+
+        let mut sorted = first;
+        sorted.sort_unstable();
+        assert_eq!(sorted, (0..10).collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,268 @@
+use std::collections::HashMap;
+use std::ops::Index;
+
+use rand::Rng;
+
+use crate::graph::Graph;
+use crate::linalg::{
+    init::{FanMode, Init},
+    math::Activation,
+    matrix::Matrix,
+};
+
+use super::{function::GaussianFunction, prior::GaussianPrior, variable::GaussianVariable};
+
+struct Layer {
+    name: String,
+    size: usize,
+    activation: Activation,
+    parent: Option<String>,
+    init: Option<Init>,
+    prior: Option<GaussianPrior>,
+}
+
+// Builds a `Graph` of Gaussian layers by name. Each layer is predicted by the layer
+// added before it (or the one named by `parent`) through `activation`, so the first
+// layer's activation is unused. Weight shapes follow from the layer sizes, and
+// initialisers default to one suited to the activation.
+#[derive(Default)]
+pub struct GraphBuilder {
+    layers: Vec<Layer>,
+    parent: Option<String>,
+    observed: Vec<String>,
+    seed: Option<u64>,
+}
+
+impl GraphBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn layer(mut self, name: &str, size: usize, activation: Activation) -> Self {
+        let parent = self
+            .parent
+            .take()
+            .or_else(|| self.layers.last().map(|layer| layer.name.clone()));
+        self.layers.push(Layer {
+            name: name.to_string(),
+            size,
+            activation,
+            parent,
+            init: None,
+            prior: None,
+        });
+        self
+    }
+
+    // The next layer is predicted by `name` rather than the previous layer.
+    pub fn parent(mut self, name: &str) -> Self {
+        self.parent = Some(name.to_string());
+        self
+    }
+
+    // Initialiser for the weights into the last layer added.
+    pub fn init(mut self, init: Init) -> Self {
+        self.last_layer("init").init = Some(init);
+        self
+    }
+
+    pub fn prior(mut self, prior: GaussianPrior) -> Self {
+        self.last_layer("prior").prior = Some(prior);
+        self
+    }
+
+    // Observed layers are clamped to their data.
+    pub fn observe(mut self, name: &str) -> Self {
+        self.observed.push(name.to_string());
+        self
+    }
+
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    pub fn build(self, batch_size: usize) -> (Graph<GaussianVariable, GaussianFunction>, Handles) {
+        let mut graph = match self.seed {
+            Some(seed) => Graph::with_seed(seed),
+            None => Graph::new(),
+        };
+        let mut handles = Handles::default();
+
+        for layer in self.layers.iter() {
+            assert!(
+                handles.get(&layer.name).is_none(),
+                "duplicate layer `{}`",
+                layer.name
+            );
+            let fixed = self.observed.contains(&layer.name);
+            let data = initial_data(batch_size, layer.size, fixed, graph.rng());
+            let mut variable = GaussianVariable::new(data, fixed);
+            if let Some(prior) = layer.prior.clone() {
+                variable = variable.with_prior(prior);
+            }
+            let index = graph.add_node(variable);
+            handles.insert(&layer.name, index);
+        }
+
+        for name in self.observed.iter() {
+            assert!(handles.get(name).is_some(), "unknown layer `{}`", name);
+        }
+
+        for layer in self.layers.iter() {
+            let parent = match layer.parent.as_ref() {
+                Some(parent) => parent,
+                None => continue,
+            };
+            let source = handles
+                .get(parent)
+                .unwrap_or_else(|| panic!("unknown parent `{}` of `{}`", parent, layer.name));
+            let source_size = graph.get_node(source).unwrap().size;
+            let init = layer.init.unwrap_or_else(|| default_init(layer.activation));
+            let function = GaussianFunction::from_init_with_rng(
+                source_size,
+                layer.size,
+                init,
+                layer.activation,
+                graph.rng(),
+            );
+            graph.add_edge(source, handles[&layer.name], function);
+        }
+
+        (graph, handles)
+    }
+
+    fn last_layer(&mut self, setting: &str) -> &mut Layer {
+        self.layers
+            .last_mut()
+            .unwrap_or_else(|| panic!("`{}` must follow a layer", setting))
+    }
+}
+
+// Observed layers start at zero until data is loaded, latent ones at small noise.
+pub(crate) fn initial_data(rows: usize, size: usize, observed: bool, rng: &mut impl Rng) -> Matrix {
+    if observed {
+        Matrix::zeros(rows, size)
+    } else {
+        Matrix::normal_with_rng(rows, size, 0.0, 0.05, rng)
+    }
+}
+
+// Kaiming for rectifier-like activations, Xavier for the rest.
+pub(crate) fn default_init(activation: Activation) -> Init {
+    match activation {
+        Activation::ReLU
+        | Activation::LeakyReLU(_)
+        | Activation::ELU(_)
+        | Activation::GELU
+        | Activation::Softplus
+        | Activation::SiLU => Init::KaimingNormal(FanMode::FanIn),
+        Activation::Linear | Activation::Tanh | Activation::Sigmoid | Activation::Softmax => {
+            Init::XavierUniform
+        }
+    }
+}
+
+// Node indices of a built graph by layer name.
+#[derive(Clone, Debug, Default)]
+pub struct Handles {
+    names: Vec<String>,
+    indices: HashMap<String, usize>,
+}
+
+impl Handles {
+    pub(crate) fn insert(&mut self, name: &str, index: usize) {
+        self.names.push(name.to_string());
+        self.indices.insert(name.to_string(), index);
+    }
+
+    pub fn get(&self, name: &str) -> Option<usize> {
+        self.indices.get(name).copied()
+    }
+
+    pub fn name(&self, index: usize) -> Option<&str> {
+        self.names
+            .iter()
+            .find(|name| self.indices[name.as_str()] == index)
+            .map(|name| name.as_str())
+    }
+
+    // Layer names in the order they were added.
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+}
+
+impl Index<&str> for Handles {
+    type Output = usize;
+
+    fn index(&self, name: &str) -> &usize {
+        self.indices
+            .get(name)
+            .unwrap_or_else(|| panic!("unknown layer `{}`", name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::Variable;
+    use crate::linalg::vector::Vector;
+
+    #[test]
+    fn test_build_chain() {
+        let (graph, handles) = GraphBuilder::new()
+            .layer("h2", 8, Activation::Linear)
+            .layer("h1", 16, Activation::ReLU)
+            .layer("x", 4, Activation::Sigmoid)
+            .init(Init::Zeros)
+            .observe("x")
+            .seed(0)
+            .build(3);
+
+        let (h2, h1, x) = (handles["h2"], handles["h1"], handles["x"]);
+        assert_eq!(handles.names(), ["h2", "h1", "x"]);
+        assert_eq!(handles.name(h1), Some("h1"));
+        assert_eq!(graph.topological_order(), Ok(vec![h2, h1, x]));
+        assert!(graph.get_node(x).unwrap().fixed());
+        assert!(!graph.get_node(h1).unwrap().fixed());
+        assert_eq!(graph.get_node(h1).unwrap().data.rows, 3);
+
+        let edges: Vec<_> = graph.get_edges().collect();
+        assert_eq!((edges[0].source, edges[0].target), (h2, h1));
+        assert_eq!(edges[0].function.weights().rows, 8);
+        assert_eq!(edges[0].function.weights().cols, 16);
+        assert_eq!(edges[1].function.weights(), &Matrix::zeros(16, 4));
+    }
+
+    #[test]
+    fn test_build_branches() {
+        let prior = GaussianPrior::learnable(Vector::zeros(2), Vector::ones(2));
+        let (graph, handles) = GraphBuilder::new()
+            .layer("mu", 2, Activation::Linear)
+            .prior(prior.clone())
+            .layer("a", 5, Activation::Linear)
+            .parent("mu")
+            .layer("b", 6, Activation::Tanh)
+            .observe("a")
+            .observe("b")
+            .build(4);
+
+        assert_eq!(
+            graph.children(handles["mu"]),
+            vec![handles["a"], handles["b"]]
+        );
+        assert_eq!(graph.get_node(handles["mu"]).unwrap().prior(), Some(&prior));
+        // Two edges, plus the prior mean and precision of `mu`.
+        assert_eq!(graph.num_parameters(), 2 * 5 + 5 + 2 * 6 + 6 + 2 * 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "unknown layer `y`")]
+    fn test_unknown_observed_layer() {
+        GraphBuilder::new()
+            .layer("x", 2, Activation::Linear)
+            .observe("y")
+            .build(1);
+    }
+}
@@ -1,72 +1,452 @@
-use crate::graph::Function;
-use crate::linalg::{
-    math::{Activation, ActivationFunction},
-    matrix::Matrix,
-    vector::Vector,
-};
+use rand::Rng;
 
-use super::variable::GaussianVariable;
+use crate::graph::{params::Tensors, Function, Variable};
+use crate::linalg::{init::Init, math::Nonlinearity, matrix::Matrix, vector::Vector};
+
+// Where the activation sits in the prediction: `f(x) W + b`, `f(x W) + b` or `f(x W + b)`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Placement {
+    Source,
+    #[default]
+    Product,
+    Output,
+}
+
+// Structure of the learnable precision on the prediction error. `Scalar` and
+// `Diagonal` store log-precisions, `Full` stores a Cholesky factor of the precision
+// matrix with a log-diagonal.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Covariance {
+    #[default]
+    Identity,
+    Scalar,
+    Diagonal,
+    Full,
+}
+
+// `precision` holds the parameters described by `Covariance`, and is empty for
+// `Covariance::Identity`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GaussianParams {
+    pub weights: Matrix,
+    pub bias: Vector,
+    pub precision: Matrix,
+}
+
+impl Tensors for GaussianParams {
+    fn flatten(&self) -> Vec<f64> {
+        let mut values = self.weights.flatten();
+        values.extend(self.bias.flatten());
+        values.extend(self.precision.flatten());
+        values
+    }
+
+    fn flatten_mut(&mut self) -> Vec<&mut f64> {
+        let mut values = self.weights.flatten_mut();
+        values.extend(self.bias.flatten_mut());
+        values.extend(self.precision.flatten_mut());
+        values
+    }
+
+    fn named(&self) -> Vec<(&'static str, &dyn Tensors)> {
+        vec![
+            ("weights", &self.weights),
+            ("bias", &self.bias),
+            ("precision", &self.precision),
+        ]
+    }
+
+    fn named_mut(&mut self) -> Vec<(&'static str, &mut dyn Tensors)> {
+        vec![
+            ("weights", &mut self.weights),
+            ("bias", &mut self.bias),
+            ("precision", &mut self.precision),
+        ]
+    }
+}
 
 pub struct GaussianFunction {
-    weights: Matrix,
-    bias: Vector,
-    activation: ActivationFunction,
+    params: GaussianParams,
+    activation: Box<dyn Nonlinearity>,
+    placement: Placement,
+    covariance: Covariance,
+    learning_rate: f64,
     fixed: bool,
 }
 
 impl GaussianFunction {
-    pub fn new(weights: Matrix, bias: Vector, activation: Activation) -> Self {
-        let activation = ActivationFunction::new(activation);
+    pub fn new(weights: Matrix, bias: Vector, activation: impl Nonlinearity + 'static) -> Self {
         Self {
-            weights,
-            bias,
-            activation,
+            params: GaussianParams {
+                weights,
+                bias,
+                precision: Matrix::zeros(0, 0),
+            },
+            activation: Box::new(activation),
+            placement: Placement::default(),
+            covariance: Covariance::default(),
+            learning_rate: 0.001,
             fixed: false,
         }
     }
+
+    pub fn from_init(
+        inp_size: usize,
+        out_size: usize,
+        init: Init,
+        activation: impl Nonlinearity + 'static,
+    ) -> Self {
+        Self::from_init_with_rng(
+            inp_size,
+            out_size,
+            init,
+            activation,
+            &mut rand::thread_rng(),
+        )
+    }
+
+    pub fn from_init_with_rng(
+        inp_size: usize,
+        out_size: usize,
+        init: Init,
+        activation: impl Nonlinearity + 'static,
+        rng: &mut impl Rng,
+    ) -> Self {
+        let weights = Matrix::init_with_rng(inp_size, out_size, init, rng);
+        Self::new(weights, Vector::zeros(out_size), activation)
+    }
+
+    pub fn with_learning_rate(mut self, learning_rate: f64) -> Self {
+        self.learning_rate = learning_rate;
+        self
+    }
+
+    pub fn learning_rate(&self) -> f64 {
+        self.learning_rate
+    }
+
+    pub fn with_placement(mut self, placement: Placement) -> Self {
+        self.placement = placement;
+        self
+    }
+
+    pub fn placement(&self) -> Placement {
+        self.placement
+    }
+
+    pub fn weights(&self) -> &Matrix {
+        &self.params.weights
+    }
+
+    pub fn bias(&self) -> &Vector {
+        &self.params.bias
+    }
+
+    // Diagonal of the precision matrix, one entry per output dimension.
+    pub fn precision(&self) -> Vector {
+        let size = self.params.weights.cols;
+        match self.covariance {
+            Covariance::Identity => Vector::ones(size),
+            Covariance::Scalar => Vector::new(vec![self.params.precision.data[0][0].exp(); size]),
+            Covariance::Diagonal => {
+                Vector::new(self.params.precision.data[0].clone()).apply(f64::exp)
+            }
+            Covariance::Full => {
+                let matrix = self.precision_matrix();
+                Vector::new((0..size).map(|j| matrix.data[j][j]).collect())
+            }
+        }
+    }
+
+    pub fn precision_matrix(&self) -> Matrix {
+        match self.covariance {
+            Covariance::Full => {
+                let factor = self.cholesky_factor();
+                factor.matmul(&factor.transpose())
+            }
+            _ => {
+                let precision = self.precision();
+                let mut matrix = Matrix::zeros(precision.size, precision.size);
+                for j in 0..precision.size {
+                    matrix.data[j][j] = precision.data[j];
+                }
+                matrix
+            }
+        }
+    }
+
+    pub fn covariance(&self) -> Covariance {
+        self.covariance
+    }
+
+    pub fn with_scalar_precision(mut self, precision: f64) -> Self {
+        assert!(precision > 0.0);
+        self.covariance = Covariance::Scalar;
+        self.params.precision = Matrix::new(vec![vec![precision.ln()]]);
+        self
+    }
+
+    pub fn with_diagonal_precision(mut self, precision: Vector) -> Self {
+        assert_eq!(precision.size, self.params.weights.cols);
+        assert!(precision.data.iter().all(|&p| p > 0.0));
+        self.covariance = Covariance::Diagonal;
+        self.params.precision = Matrix::new(vec![precision.apply(f64::ln).data]);
+        self
+    }
+
+    pub fn with_full_precision(mut self, precision: Matrix) -> Self {
+        assert_eq!(precision.rows, self.params.weights.cols);
+        assert_eq!(precision.cols, self.params.weights.cols);
+        let mut factor = precision
+            .cholesky()
+            .expect("precision must be symmetric positive definite");
+        for j in 0..factor.rows {
+            factor.data[j][j] = factor.data[j][j].ln();
+        }
+        self.covariance = Covariance::Full;
+        self.params.precision = factor;
+        self
+    }
+
+    // Lower-triangular factor of the precision matrix, with the stored log-diagonal
+    // exponentiated so that the factor stays positive definite.
+    fn cholesky_factor(&self) -> Matrix {
+        let size = self.params.precision.rows;
+        let mut factor = Matrix::zeros(size, size);
+        for i in 0..size {
+            for j in 0..i {
+                factor.data[i][j] = self.params.precision.data[i][j];
+            }
+            factor.data[i][i] = self.params.precision.data[i][i].exp();
+        }
+        factor
+    }
+
+    fn log_det(&self) -> f64 {
+        let params = &self.params.precision;
+        match self.covariance {
+            Covariance::Identity => 0.0,
+            Covariance::Scalar => self.params.weights.cols as f64 * params.data[0][0],
+            Covariance::Diagonal => params.data[0].iter().sum(),
+            Covariance::Full => 2.0 * (0..params.rows).map(|j| params.data[j][j]).sum::<f64>(),
+        }
+    }
+
+    fn weight(&self, err: &Matrix) -> Matrix {
+        match self.covariance {
+            Covariance::Full => err.matmul(&self.precision_matrix()),
+            _ => err * &self.precision(),
+        }
+    }
+
+    // Descent direction for the precision parameters, -dE/dparams.
+    fn precision_deriv(&self, errors: &Errors, batch_size: usize) -> Matrix {
+        let residual = (&errors.err * &errors.weighted).apply(|v| 0.5 * (1.0 - v));
+        match self.covariance {
+            Covariance::Identity => Matrix::zeros(0, 0),
+            Covariance::Scalar => Matrix::new(vec![vec![residual.sum(0).data.iter().sum()]]),
+            Covariance::Diagonal => Matrix::new(vec![residual.sum(0).data]),
+            Covariance::Full => {
+                let factor = self.cholesky_factor();
+                let grad = errors.err.transpose().matmul(&errors.err).matmul(&factor);
+                let mut deriv = Matrix::zeros(factor.rows, factor.cols);
+                for i in 0..factor.rows {
+                    for j in 0..i {
+                        deriv.data[i][j] = -grad.data[i][j];
+                    }
+                    deriv.data[i][i] = batch_size as f64 - grad.data[i][i] * factor.data[i][i];
+                }
+                deriv
+            }
+        }
+    }
+
+    fn errors(&self, inp: &Matrix, target: &Matrix) -> Errors {
+        assert_eq!(inp.cols, self.params.weights.rows);
+        match self.placement {
+            Placement::Source => {
+                let source = self.activation.forward(inp);
+                let pred = &source.matmul(&self.params.weights) + &self.params.bias;
+                let err = target - &pred;
+                let weighted = self.weight(&err);
+                Errors {
+                    source,
+                    err,
+                    deriv: weighted.clone(),
+                    weighted,
+                }
+            }
+            Placement::Product => {
+                let product = inp.matmul(&self.params.weights);
+                let pred = &self.activation.forward(&product) + &self.params.bias;
+                let err = target - &pred;
+                let weighted = self.weight(&err);
+                let deriv = self.activation.jvp(&product, &weighted);
+                Errors {
+                    source: inp.clone(),
+                    err,
+                    weighted,
+                    deriv,
+                }
+            }
+            Placement::Output => {
+                let product = &inp.matmul(&self.params.weights) + &self.params.bias;
+                let pred = self.activation.forward(&product);
+                let err = target - &pred;
+                let weighted = self.weight(&err);
+                let deriv = self.activation.jvp(&product, &weighted);
+                Errors {
+                    source: inp.clone(),
+                    err,
+                    weighted,
+                    deriv,
+                }
+            }
+        }
+    }
 }
 
-impl Function<GaussianVariable> for GaussianFunction {
-    fn forward(&self, inp: &GaussianVariable) -> Matrix {
-        assert_eq!(inp.size, self.weights.rows);
-        let product = inp.data.matmul(&self.weights);
-        &self.activation.forward(&product) + &self.bias
-    }
-
-    fn backward(&self, inp: &GaussianVariable, target: &GaussianVariable) -> (Matrix, Matrix) {
-        assert_eq!(inp.size, self.weights.rows);
-        let product = &inp.data.matmul(&self.weights);
-        let pred = &self.activation.forward(&product) + &self.bias;
-        let err = &target.data - &pred;
-        let fn_deriv = self.activation.backward(&product);
-        let err_deriv = &err * &fn_deriv;
-        let err_proj = err_deriv.matmul(&self.weights.transpose());
-        let source_deriv = err_proj;
-        let target_deriv = -err;
+// The matrix multiplied by the weights, the raw and precision-weighted prediction
+// errors, and the weighted error propagated back to the output of the multiplication.
+struct Errors {
+    source: Matrix,
+    err: Matrix,
+    weighted: Matrix,
+    deriv: Matrix,
+}
+
+impl<T: Variable<Gradient = Matrix>> Function<T> for GaussianFunction {
+    type Params = GaussianParams;
+    type Gradient = GaussianParams;
+
+    fn forward(&self, inp: &T) -> Matrix {
+        assert_eq!(inp.data().cols, self.params.weights.rows);
+        match self.placement {
+            Placement::Source => {
+                &self
+                    .activation
+                    .forward(inp.data())
+                    .matmul(&self.params.weights)
+                    + &self.params.bias
+            }
+            Placement::Product => {
+                let product = inp.data().matmul(&self.params.weights);
+                &self.activation.forward(&product) + &self.params.bias
+            }
+            Placement::Output => {
+                let product = &inp.data().matmul(&self.params.weights) + &self.params.bias;
+                self.activation.forward(&product)
+            }
+        }
+    }
+
+    fn energy(&self, inp: &T, target: &T) -> f64 {
+        let errors = self.errors(inp.data(), target.data());
+        let log_det = inp.data().rows as f64 * self.log_det();
+        let weighted_sq: f64 = (&errors.err * &errors.weighted).data.iter().flatten().sum();
+        0.5 * (weighted_sq - log_det)
+    }
+
+    fn backward(&self, inp: &T, target: &T) -> (Matrix, Matrix) {
+        let errors = self.errors(inp.data(), target.data());
+        let err_proj = errors.deriv.matmul(&self.params.weights.transpose());
+        let source_deriv = match self.placement {
+            Placement::Source => self.activation.jvp(inp.data(), &err_proj),
+            Placement::Product | Placement::Output => err_proj,
+        };
+        let target_deriv = -errors.weighted;
         (source_deriv, target_deriv)
     }
 
-    fn backward_params(
-        &self,
-        inp: &GaussianVariable,
-        target: &GaussianVariable,
-    ) -> (Matrix, Vector) {
-        assert_eq!(inp.size, self.weights.rows);
-        let product = &inp.data.matmul(&self.weights);
-        let pred = &self.activation.forward(&product) + &self.bias;
-        let err = &target.data - &pred;
-        let fn_deriv = self.activation.backward(&product);
-        let err_deriv = &err * &fn_deriv;
-        let weight_deriv = inp.data.transpose().matmul(&err_deriv);
-        let bias_deriv = err.sum(0);
-        (weight_deriv, bias_deriv)
+    fn backward_params(&self, inp: &T, target: &T) -> GaussianParams {
+        let errors = self.errors(inp.data(), target.data());
+        let weight_deriv = errors.source.transpose().matmul(&errors.deriv);
+        let bias_deriv = match self.placement {
+            Placement::Product => errors.weighted.sum(0),
+            Placement::Source | Placement::Output => errors.deriv.sum(0),
+        };
+        let precision_deriv = self.precision_deriv(&errors, inp.data().rows);
+        GaussianParams {
+            weights: weight_deriv,
+            bias: bias_deriv,
+            precision: precision_deriv,
+        }
     }
 
-    fn update(&mut self, derivatives: (Matrix, Vector)) {
+    fn params(&self) -> &GaussianParams {
+        &self.params
+    }
+
+    fn params_mut(&mut self) -> &mut GaussianParams {
+        &mut self.params
+    }
+
+    fn update(&mut self, derivatives: GaussianParams) {
         if !self.fixed {
-            let (weight_deriv, bias_deriv) = derivatives;
-            self.weights += &weight_deriv.apply(|v| v * 0.001);
-            self.bias += &bias_deriv.apply(|v| v * 0.001);
+            let lr = self.learning_rate;
+            self.params.weights += &derivatives.weights.apply(|v| v * lr);
+            self.params.bias += &derivatives.bias.apply(|v| v * lr);
+            self.params.precision += &derivatives.precision.apply(|v| v * lr);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gaussian::variable::GaussianVariable;
+    use crate::linalg::math::Activation;
+
+    #[test]
+    fn test_forward_placement() {
+        let inp = GaussianVariable::new(Matrix::new(vec![vec![-1.0, 2.0]]), false);
+        let weights = Matrix::new(vec![vec![1.0, -1.0], vec![1.0, 1.0]]);
+        let bias = Vector::new(vec![-2.0, 0.5]);
+        let function = |placement| {
+            GaussianFunction::new(weights.clone(), bias.clone(), Activation::ReLU)
+                .with_placement(placement)
+        };
+
+        let pred = function(Placement::Source).forward(&inp);
+        assert_eq!(pred, Matrix::new(vec![vec![0.0, 2.5]]));
+        let pred = function(Placement::Product).forward(&inp);
+        assert_eq!(pred, Matrix::new(vec![vec![-1.0, 3.5]]));
+        let pred = function(Placement::Output).forward(&inp);
+        assert_eq!(pred, Matrix::new(vec![vec![0.0, 3.5]]));
+    }
+
+    #[test]
+    fn test_precision_weights_energy() {
+        let inp = GaussianVariable::new(Matrix::new(vec![vec![1.0, 0.0]]), false);
+        let target = GaussianVariable::new(Matrix::new(vec![vec![3.0, 1.0]]), true);
+        let function =
+            GaussianFunction::new(Matrix::identity(2), Vector::zeros(2), Activation::Linear);
+        assert!((function.energy(&inp, &target) - 2.5).abs() < 1e-12);
+
+        let function = function.with_diagonal_precision(Vector::new(vec![2.0, 0.5]));
+        let expected = 0.5 * (2.0 * 4.0 + 0.5 * 1.0) - 0.5 * (2.0f64.ln() + 0.5f64.ln());
+        assert!((function.energy(&inp, &target) - expected).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_full_precision_roundtrip() {
+        let precision = Matrix::new(vec![vec![2.0, 0.5], vec![0.5, 1.0]]);
+        let function =
+            GaussianFunction::new(Matrix::identity(2), Vector::zeros(2), Activation::Linear)
+                .with_full_precision(precision.clone());
+        let result = function.precision_matrix();
+        for (a, b) in result
+            .data
+            .iter()
+            .flatten()
+            .zip(precision.data.iter().flatten())
+        {
+            assert!((a - b).abs() < 1e-12);
         }
+
+        let inp = GaussianVariable::new(Matrix::new(vec![vec![1.0, 0.0]]), false);
+        let target = GaussianVariable::new(Matrix::new(vec![vec![3.0, 1.0]]), true);
+        let expected = 0.5 * (2.0 * 4.0 + 2.0 * 0.5 * 2.0 + 1.0) - 0.5 * 1.75f64.ln();
+        assert!((function.energy(&inp, &target) - expected).abs() < 1e-12);
     }
 }
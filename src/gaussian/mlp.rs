@@ -0,0 +1,195 @@
+use crate::graph::Graph;
+use crate::linalg::{init::Init, math::Activation, matrix::Matrix};
+
+use super::{
+    function::{GaussianFunction, Placement},
+    variable::GaussianVariable,
+};
+
+// Discriminative predictive coding: inputs predict labels through a chain of
+// `f(x W + b)` layers, so that after `forward_init` the latents hold the activations
+// of the equivalent MLP. With the labels clamped, inference pushes the output error
+// back through the hidden layers, and at equilibrium the weight updates approximate
+// those of backprop.
+pub struct Mlp {
+    graph: Graph<GaussianVariable, GaussianFunction>,
+    layers: Vec<usize>,
+}
+
+impl Mlp {
+    // `activations[i]` is applied to the prediction of layer `i + 1`.
+    pub fn new(sizes: &[usize], activations: &[Activation]) -> Self {
+        Self::from_graph(Graph::new(), sizes, activations)
+    }
+
+    pub fn with_seed(sizes: &[usize], activations: &[Activation], seed: u64) -> Self {
+        Self::from_graph(Graph::with_seed(seed), sizes, activations)
+    }
+
+    fn from_graph(
+        mut graph: Graph<GaussianVariable, GaussianFunction>,
+        sizes: &[usize],
+        activations: &[Activation],
+    ) -> Self {
+        assert!(sizes.len() >= 2);
+        assert_eq!(activations.len(), sizes.len() - 1);
+
+        let layers: Vec<usize> = sizes
+            .iter()
+            .map(|&size| graph.add_node(GaussianVariable::new(Matrix::zeros(1, size), false)))
+            .collect();
+        for (i, &activation) in activations.iter().enumerate() {
+            let function = GaussianFunction::from_init_with_rng(
+                sizes[i],
+                sizes[i + 1],
+                Init::XavierUniform,
+                activation,
+                graph.rng(),
+            )
+            .with_placement(Placement::Output);
+            graph.add_edge(layers[i], layers[i + 1], function);
+        }
+
+        graph.clamp(layers[0]);
+        graph.set_labels(&layers[layers.len() - 1..]);
+        Self { graph, layers }
+    }
+
+    pub fn graph(&self) -> &Graph<GaussianVariable, GaussianFunction> {
+        &self.graph
+    }
+
+    pub fn graph_mut(&mut self) -> &mut Graph<GaussianVariable, GaussianFunction> {
+        &mut self.graph
+    }
+
+    pub fn layers(&self) -> &[usize] {
+        &self.layers
+    }
+
+    pub fn input(&self) -> usize {
+        self.layers[0]
+    }
+
+    pub fn output(&self) -> usize {
+        self.layers[self.layers.len() - 1]
+    }
+
+    // One training step on a batch: clamp inputs and labels, start the latents at
+    // their feed-forward values, relax, then learn. Returns the energy at equilibrium.
+    pub fn train_batch(&mut self, inputs: &Matrix, labels: &Matrix, num_iterations: usize) -> f64 {
+        let (input, output) = (self.input(), self.output());
+        self.graph.train();
+        self.set_inputs(inputs);
+        self.graph
+            .get_node_mut(output)
+            .unwrap()
+            .set_data(labels.clone());
+
+        self.graph.forward_init(&[input]);
+        for _ in 0..num_iterations {
+            self.graph.infer();
+        }
+        let energy = self.graph.energy();
+        self.graph.learn();
+        energy
+    }
+
+    // Infers the labels with only the inputs clamped.
+    pub fn predict(&mut self, inputs: &Matrix, num_iterations: usize) -> Matrix {
+        let (input, output) = (self.input(), self.output());
+        self.graph.eval();
+        self.set_inputs(inputs);
+
+        self.graph.forward_init(&[input]);
+        for _ in 0..num_iterations {
+            self.graph.infer();
+        }
+        self.graph.get_node(output).unwrap().data.clone()
+    }
+
+    // Resizes every latent to the batch so `forward_init` can overwrite it.
+    fn set_inputs(&mut self, inputs: &Matrix) {
+        let input = self.input();
+        for &layer in self.layers.iter() {
+            let node = self.graph.get_node_mut(layer).unwrap();
+            if layer == input {
+                node.set_data(inputs.clone());
+            } else if node.data.rows != inputs.rows {
+                node.set_data(Matrix::zeros(inputs.rows, node.size));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::Variable;
+
+    fn cosine(a: &Matrix, b: &Matrix) -> f64 {
+        let dot: f64 = (a * b).data.iter().flatten().sum();
+        let norm = |m: &Matrix| (m * m).data.iter().flatten().sum::<f64>().sqrt();
+        dot / (norm(a) * norm(b))
+    }
+
+    #[test]
+    fn test_approximates_backprop() {
+        let mut mlp = Mlp::with_seed(&[3, 4, 2], &[Activation::Tanh, Activation::Linear], 0);
+        let inputs = Matrix::normal_with_rng(8, 3, 0.0, 1.0, mlp.graph_mut().rng());
+        let noise = Matrix::normal_with_rng(8, 2, 0.0, 0.001, mlp.graph_mut().rng());
+
+        let (w0, b0, w1, b1) = {
+            let edges: Vec<_> = mlp.graph().get_edges().collect();
+            (
+                edges[0].function.weights().clone(),
+                edges[0].function.bias().clone(),
+                edges[1].function.weights().clone(),
+                edges[1].function.bias().clone(),
+            )
+        };
+        let hidden = (&inputs.matmul(&w0) + &b0).apply(f64::tanh);
+        let outputs = &hidden.matmul(&w1) + &b1;
+        let labels = &outputs + &noise;
+
+        // Descent directions of 0.5 * |labels - outputs|^2.
+        let output_error = &labels - &outputs;
+        let hidden_error = &output_error.matmul(&w1.transpose()) * &hidden.apply(|h| 1.0 - h * h);
+        let bp_w1 = hidden.transpose().matmul(&output_error);
+        let bp_w0 = inputs.transpose().matmul(&hidden_error);
+
+        mlp.train_batch(&inputs, &labels, 2000);
+        let edges: Vec<_> = mlp.graph().get_edges().collect();
+        let pc_w0 = edges[0].function.weights() - &w0;
+        let pc_w1 = edges[1].function.weights() - &w1;
+
+        // Equal precisions on every layer only approximate backprop, as the hidden
+        // layer absorbs part of the output error at equilibrium.
+        assert!(cosine(&pc_w0, &bp_w0) > 0.98);
+        assert!(cosine(&pc_w1, &bp_w1) > 0.98);
+    }
+
+    #[test]
+    fn test_train_and_predict() {
+        let mut mlp = Mlp::with_seed(&[2, 8, 1], &[Activation::Tanh, Activation::Linear], 1);
+        let inputs = Matrix::normal_with_rng(16, 2, 0.0, 1.0, mlp.graph_mut().rng());
+        let labels = inputs.matmul(&Matrix::new(vec![vec![1.0], vec![-1.0]]));
+        let mse = |preds: &Matrix| {
+            let error = preds - &labels;
+            (&error * &error).mean()
+        };
+
+        let initial = mse(&mlp.predict(&inputs, 0));
+        for _ in 0..200 {
+            mlp.train_batch(&inputs, &labels, 10);
+        }
+        let preds = mlp.predict(&inputs, 10);
+        assert!(mse(&preds) < initial);
+        assert!(!mlp.graph().get_node(mlp.output()).unwrap().fixed());
+
+        // Predictions start at the feed-forward output, where the energy is zero.
+        assert!(mlp.graph().energy() < 1e-12);
+        assert_eq!(preds.rows, 16);
+        assert_eq!(mlp.graph().get_node(mlp.input()).unwrap().data, inputs);
+    }
+}
@@ -1,2 +1,5 @@
+pub mod builder;
 pub mod function;
+pub mod mlp;
+pub mod prior;
 pub mod variable;
@@ -0,0 +1,125 @@
+use crate::linalg::{matrix::Matrix, vector::Vector};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GaussianPrior {
+    pub mean: Vector,
+    log_precision: Vector,
+    learnable: bool,
+}
+
+impl GaussianPrior {
+    pub fn fixed(mean: Vector, precision: Vector) -> Self {
+        Self::new(mean, precision, false)
+    }
+
+    pub fn learnable(mean: Vector, precision: Vector) -> Self {
+        Self::new(mean, precision, true)
+    }
+
+    fn new(mean: Vector, precision: Vector, learnable: bool) -> Self {
+        assert_eq!(mean.size, precision.size);
+        assert!(precision.data.iter().all(|&p| p > 0.0));
+        Self {
+            mean,
+            log_precision: precision.apply(f64::ln),
+            learnable,
+        }
+    }
+
+    pub fn precision(&self) -> Vector {
+        self.log_precision.apply(f64::exp)
+    }
+
+    // The learned tensors: the mean and the log-precision.
+    pub(crate) fn tensors(&self) -> (&Vector, &Vector) {
+        (&self.mean, &self.log_precision)
+    }
+
+    pub(crate) fn tensors_mut(&mut self) -> (&mut Vector, &mut Vector) {
+        (&mut self.mean, &mut self.log_precision)
+    }
+
+    pub fn energy(&self, data: &Matrix) -> f64 {
+        let err = data - &self.broadcast_mean(data.rows);
+        let weighted_sq: f64 = (&(&err * &err) * &self.precision())
+            .data
+            .iter()
+            .flatten()
+            .sum();
+        let log_det = data.rows as f64 * self.log_precision.data.iter().sum::<f64>();
+        0.5 * (weighted_sq - log_det)
+    }
+
+    pub fn backward(&self, data: &Matrix) -> Matrix {
+        let err = data - &self.broadcast_mean(data.rows);
+        -(&err * &self.precision())
+    }
+
+    pub fn learn(&mut self, data: &Matrix) {
+        if self.learnable {
+            let err = data - &self.broadcast_mean(data.rows);
+            let weighted = &err * &self.precision();
+            let mean_deriv = weighted.sum(0);
+            let precision_deriv = (&err * &weighted).apply(|v| 0.5 * (1.0 - v)).sum(0);
+            self.mean += &mean_deriv.apply(|v| v * 0.001);
+            self.log_precision += &precision_deriv.apply(|v| v * 0.001);
+        }
+    }
+
+    fn broadcast_mean(&self, rows: usize) -> Matrix {
+        &Matrix::zeros(rows, self.mean.size) + &self.mean
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gaussian::{function::GaussianFunction, variable::GaussianVariable};
+    use crate::graph::Graph;
+
+    #[test]
+    fn test_backward_matches_energy() {
+        let prior = GaussianPrior::fixed(Vector::new(vec![1.0, -1.0]), Vector::new(vec![2.0, 0.5]));
+        let data = Matrix::new(vec![vec![0.3, 0.4], vec![-1.2, 2.0]]);
+        let analytic = prior.backward(&data);
+        let eps = 1e-6;
+        for i in 0..data.rows {
+            for j in 0..data.cols {
+                let mut plus = data.clone();
+                let mut minus = data.clone();
+                plus.data[i][j] += eps;
+                minus.data[i][j] -= eps;
+                let numerical = (prior.energy(&plus) - prior.energy(&minus)) / (2.0 * eps);
+                assert!((analytic.data[i][j] + numerical).abs() < 1e-6);
+            }
+        }
+    }
+
+    #[test]
+    fn test_root_is_pulled_towards_prior() {
+        let prior = GaussianPrior::fixed(Vector::new(vec![3.0, -2.0]), Vector::ones(2));
+        let root = GaussianVariable::new(Matrix::zeros(1, 2), false).with_prior(prior);
+        let mut graph = Graph::<GaussianVariable, GaussianFunction>::new();
+        let index = graph.add_node(root);
+
+        let initial = graph.energy();
+        for _ in 0..5000 {
+            graph.infer();
+        }
+        assert!(graph.energy() < initial);
+        let data = &graph.get_node(index).unwrap().data;
+        assert!((data.data[0][0] - 3.0).abs() < 0.1);
+        assert!((data.data[0][1] + 2.0).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_learnable_prior_fits_data() {
+        let data = Matrix::new(vec![vec![1.0], vec![3.0]]);
+        let mut prior = GaussianPrior::learnable(Vector::zeros(1), Vector::ones(1));
+        for _ in 0..20000 {
+            prior.learn(&data);
+        }
+        assert!((prior.mean.data[0] - 2.0).abs() < 1e-3);
+        assert!((prior.precision().data[0] - 1.0).abs() < 1e-2);
+    }
+}
@@ -1,17 +1,35 @@
-use crate::graph::Variable;
+use crate::graph::{params::Tensors, Variable};
 use crate::linalg::matrix::Matrix;
 
+use super::prior::GaussianPrior;
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct GaussianVariable {
     pub size: usize,
     pub data: Matrix,
     fixed: bool,
+    prior: Option<GaussianPrior>,
 }
 
 impl GaussianVariable {
     pub fn new(data: Matrix, fixed: bool) -> Self {
         let size = data.cols;
-        Self { size, data, fixed }
+        Self {
+            size,
+            data,
+            fixed,
+            prior: None,
+        }
+    }
+
+    pub fn with_prior(mut self, prior: GaussianPrior) -> Self {
+        assert_eq!(prior.mean.size, self.size);
+        self.prior = Some(prior);
+        self
+    }
+
+    pub fn prior(&self) -> Option<&GaussianPrior> {
+        self.prior.as_ref()
     }
 
     pub fn set_data(&mut self, data: Matrix) {
@@ -21,11 +39,66 @@ impl GaussianVariable {
 }
 
 impl Variable for GaussianVariable {
-    fn update(&mut self, derivatives: &Vec<Matrix>) {
+    type Gradient = Matrix;
+
+    fn data(&self) -> &Matrix {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut Matrix {
+        &mut self.data
+    }
+
+    fn fixed(&self) -> bool {
+        self.fixed
+    }
+
+    fn set_fixed(&mut self, fixed: bool) {
+        self.fixed = fixed;
+    }
+
+    fn update(&mut self, derivatives: &[Matrix], rate: f64) {
         if !self.fixed {
             for derivative in derivatives {
-                self.data += &derivative.apply(|v| v * 0.001);
+                self.data += &derivative.apply(|v| v * rate);
+            }
+        }
+    }
+
+    fn energy(&self) -> f64 {
+        self.prior
+            .as_ref()
+            .map_or(0.0, |prior| prior.energy(&self.data))
+    }
+
+    fn backward(&self) -> Option<Matrix> {
+        self.prior.as_ref().map(|prior| prior.backward(&self.data))
+    }
+
+    fn learn(&mut self) {
+        if let Some(prior) = self.prior.as_mut() {
+            prior.learn(&self.data);
+        }
+    }
+
+    // Like edge precisions, the prior's precision is stored as its logarithm.
+    fn parameters(&self) -> Vec<(&'static str, &dyn Tensors)> {
+        match self.prior.as_ref() {
+            Some(prior) => {
+                let (mean, precision) = prior.tensors();
+                vec![("prior.mean", mean), ("prior.precision", precision)]
+            }
+            None => Vec::new(),
+        }
+    }
+
+    fn parameters_mut(&mut self) -> Vec<(&'static str, &mut dyn Tensors)> {
+        match self.prior.as_mut() {
+            Some(prior) => {
+                let (mean, precision) = prior.tensors_mut();
+                vec![("prior.mean", mean), ("prior.precision", precision)]
             }
+            None => Vec::new(),
         }
     }
 }
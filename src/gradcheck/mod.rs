@@ -0,0 +1,305 @@
+use crate::graph::{params::Tensors, Function, Variable};
+
+// Maximum relative error between finite-difference and analytic energy gradients.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GradCheck {
+    pub source: f64,
+    pub target: f64,
+    pub params: f64,
+}
+
+impl GradCheck {
+    pub fn max(&self) -> f64 {
+        self.source.max(self.target).max(self.params)
+    }
+}
+
+// `Function` derivatives point downhill, so they are compared against the negated
+// central difference of `Function::energy`. Node gradients are assumed to flatten in
+// the same order as `Variable::data`, and parameter gradients in the same order as
+// `Function::params`.
+pub fn gradcheck<T, F>(function: &mut F, inp: &T, target: &T, eps: f64) -> GradCheck
+where
+    T: Variable + Clone,
+    T::Gradient: Tensors,
+    F: Function<T>,
+{
+    let (source_deriv, target_deriv) = function.backward(inp, target);
+    let params_deriv = function.backward_params(inp, target);
+
+    let source_error = check(&source_deriv.flatten(), eps, |k, delta| {
+        let mut inp = inp.clone();
+        *inp.data_mut().flatten_mut()[k] += delta;
+        function.energy(&inp, target)
+    });
+    let target_error = check(&target_deriv.flatten(), eps, |k, delta| {
+        let mut target = target.clone();
+        *target.data_mut().flatten_mut()[k] += delta;
+        function.energy(inp, &target)
+    });
+    let params_error = check(&params_deriv.flatten(), eps, |k, delta| {
+        *function.params_mut().flatten_mut()[k] += delta;
+        let energy = function.energy(inp, target);
+        *function.params_mut().flatten_mut()[k] -= delta;
+        energy
+    });
+
+    GradCheck {
+        source: source_error,
+        target: target_error,
+        params: params_error,
+    }
+}
+
+fn check<E>(analytic: &[f64], eps: f64, mut energy: E) -> f64
+where
+    E: FnMut(usize, f64) -> f64,
+{
+    let mut max_error: f64 = 0.0;
+    for (k, value) in analytic.iter().enumerate() {
+        let numerical = (energy(k, eps) - energy(k, -eps)) / (2.0 * eps);
+        max_error = max_error.max(relative_error(-value, numerical));
+    }
+    max_error
+}
+
+fn relative_error(analytic: f64, numerical: f64) -> f64 {
+    let scale = analytic.abs().max(numerical.abs()).max(1e-4);
+    (analytic - numerical).abs() / scale
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+    use crate::bernoulli::{function::BernoulliFunction, variable::BernoulliVariable};
+    use crate::categorical::{function::CategoricalFunction, variable::CategoricalVariable};
+    use crate::gaussian::{
+        function::{GaussianFunction, Placement},
+        variable::GaussianVariable,
+    };
+    use crate::laplace::{function::LaplaceFunction, variable::LaplaceVariable};
+    use crate::linalg::{math::Activation, matrix::Matrix, vector::Vector};
+    use crate::poisson::{function::PoissonFunction, variable::PoissonVariable};
+
+    const ACTIVATIONS: [Activation; 10] = [
+        Activation::Linear,
+        Activation::ReLU,
+        Activation::Tanh,
+        Activation::Sigmoid,
+        Activation::LeakyReLU(0.1),
+        Activation::ELU(1.0),
+        Activation::GELU,
+        Activation::Softplus,
+        Activation::SiLU,
+        Activation::Softmax,
+    ];
+
+    fn check(activation: Activation, placement: Placement) -> GradCheck {
+        check_with(|function| function.with_placement(placement), activation)
+    }
+
+    fn check_with<P>(configure: P, activation: Activation) -> GradCheck
+    where
+        P: Fn(GaussianFunction) -> GaussianFunction,
+    {
+        let mut rng = StdRng::seed_from_u64(0);
+        let inp = GaussianVariable::new(Matrix::normal_with_rng(4, 3, 0.0, 1.0, &mut rng), false);
+        let target = GaussianVariable::new(Matrix::normal_with_rng(4, 5, 0.0, 1.0, &mut rng), true);
+        let weights = Matrix::normal_with_rng(3, 5, 0.0, 0.5, &mut rng);
+        let bias = Vector::new(
+            Matrix::normal_with_rng(1, 5, 0.0, 0.5, &mut rng)
+                .data
+                .remove(0),
+        );
+        let mut function = configure(GaussianFunction::new(weights, bias, activation));
+        gradcheck(&mut function, &inp, &target, 1e-6)
+    }
+
+    #[test]
+    fn test_gaussian_product_placement() {
+        for activation in ACTIVATIONS.iter() {
+            let result = check(*activation, Placement::Product);
+            assert!(result.max() < 1e-5, "{:?}: {:?}", activation, result);
+        }
+    }
+
+    #[test]
+    fn test_gaussian_source_placement() {
+        for activation in ACTIVATIONS.iter() {
+            let result = check(*activation, Placement::Source);
+            assert!(result.max() < 1e-5, "{:?}: {:?}", activation, result);
+        }
+    }
+
+    #[test]
+    fn test_gaussian_output_placement() {
+        for activation in ACTIVATIONS.iter() {
+            let result = check(*activation, Placement::Output);
+            assert!(result.max() < 1e-5, "{:?}: {:?}", activation, result);
+        }
+    }
+
+    #[test]
+    fn test_gaussian_scalar_precision() {
+        for activation in ACTIVATIONS.iter() {
+            let result = check_with(|function| function.with_scalar_precision(2.5), *activation);
+            assert!(result.max() < 1e-5, "{:?}: {:?}", activation, result);
+        }
+    }
+
+    #[test]
+    fn test_gaussian_diagonal_precision() {
+        let precision = || Vector::new(vec![0.5, 1.0, 2.0, 4.0, 8.0]);
+        for placement in [Placement::Source, Placement::Product, Placement::Output].iter() {
+            let result = check_with(
+                |function| {
+                    function
+                        .with_placement(*placement)
+                        .with_diagonal_precision(precision())
+                },
+                Activation::Tanh,
+            );
+            assert!(result.max() < 1e-5, "{:?}: {:?}", placement, result);
+        }
+    }
+
+    #[test]
+    fn test_gaussian_full_precision() {
+        let precision = || {
+            let factor = Matrix::normal_with_rng(5, 5, 0.0, 0.5, &mut StdRng::seed_from_u64(1));
+            &factor.matmul(&factor.transpose()) + &Matrix::identity(5)
+        };
+        for placement in [Placement::Source, Placement::Product, Placement::Output].iter() {
+            let result = check_with(
+                |function| {
+                    function
+                        .with_placement(*placement)
+                        .with_full_precision(precision())
+                },
+                Activation::Sigmoid,
+            );
+            assert!(result.max() < 1e-5, "{:?}: {:?}", placement, result);
+        }
+    }
+
+    // Builds the input, target and edge from a seeded rng, then checks the edge.
+    fn check_edge<T, F, B>(eps: f64, build: B) -> GradCheck
+    where
+        T: Variable + Clone,
+        T::Gradient: Tensors,
+        F: Function<T>,
+        B: FnOnce(&mut StdRng) -> (T, T, F),
+    {
+        let mut rng = StdRng::seed_from_u64(0);
+        let (inp, target, mut function) = build(&mut rng);
+        gradcheck(&mut function, &inp, &target, eps)
+    }
+
+    #[test]
+    fn test_bernoulli() {
+        let result = check_edge(1e-6, |rng| {
+            let inp = BernoulliVariable::new(Matrix::random_with_rng(4, 3, rng), false);
+            let target = Matrix::random_with_rng(4, 5, rng).apply(|v| v.round());
+            let weights = Matrix::normal_with_rng(3, 5, 0.0, 0.5, rng);
+            let function = BernoulliFunction::new(weights, Vector::new(vec![0.1; 5]));
+            (inp, BernoulliVariable::new(target, true), function)
+        });
+        assert!(result.max() < 1e-5, "{:?}", result);
+    }
+
+    #[test]
+    fn test_categorical() {
+        let result = check_edge(1e-6, |rng| {
+            let inp = CategoricalVariable::new(Matrix::normal_with_rng(4, 3, 0.0, 1.0, rng), false);
+            let target = CategoricalVariable::from_labels(&[0, 4, 2, 2], 5, true);
+            let weights = Matrix::normal_with_rng(3, 5, 0.0, 0.5, rng);
+            let function = CategoricalFunction::new(weights, Vector::new(vec![0.1; 5]));
+            (inp, target, function)
+        });
+        assert!(result.max() < 1e-5, "{:?}", result);
+    }
+
+    #[test]
+    fn test_poisson() {
+        let result = check_edge(1e-6, |rng| {
+            let inp = PoissonVariable::new(Matrix::random_with_rng(4, 3, rng), false);
+            let target = Matrix::random_with_rng(4, 5, rng).apply(|v| (v * 6.0).floor());
+            let weights = Matrix::normal_with_rng(3, 5, 0.0, 0.5, rng);
+            let function = PoissonFunction::new(weights, Vector::new(vec![0.5; 5]));
+            (inp, PoissonVariable::new(target, true), function)
+        });
+        assert!(result.max() < 1e-5, "{:?}", result);
+    }
+
+    #[test]
+    fn test_laplace() {
+        for activation in ACTIVATIONS.iter() {
+            // The L1 energy is piecewise linear in the error, so a larger step only
+            // reduces round-off.
+            let result = check_edge(1e-4, |rng| {
+                let inp = LaplaceVariable::new(Matrix::normal_with_rng(4, 3, 0.0, 1.0, rng), false);
+                let target =
+                    LaplaceVariable::new(Matrix::normal_with_rng(4, 5, 0.0, 1.0, rng), true);
+                let weights = Matrix::normal_with_rng(3, 5, 0.0, 0.5, rng);
+                let function =
+                    LaplaceFunction::new(weights, Vector::new(vec![0.1; 5]), *activation);
+                (inp, target, function.with_scale(0.5))
+            });
+            assert!(result.max() < 1e-5, "{:?}: {:?}", activation, result);
+        }
+    }
+
+    // A bias-free linear Gaussian edge whose parameters are just the weight matrix.
+    struct Projection {
+        weights: Matrix,
+    }
+
+    impl Function<GaussianVariable> for Projection {
+        type Params = Matrix;
+        type Gradient = Matrix;
+
+        fn forward(&self, inp: &GaussianVariable) -> Matrix {
+            inp.data.matmul(&self.weights)
+        }
+
+        fn energy(&self, inp: &GaussianVariable, target: &GaussianVariable) -> f64 {
+            let err = &target.data - &self.forward(inp);
+            0.5 * (&err * &err).data.iter().flatten().sum::<f64>()
+        }
+
+        fn backward(&self, inp: &GaussianVariable, target: &GaussianVariable) -> (Matrix, Matrix) {
+            let err = &target.data - &self.forward(inp);
+            (err.matmul(&self.weights.transpose()), -err)
+        }
+
+        fn backward_params(&self, inp: &GaussianVariable, target: &GaussianVariable) -> Matrix {
+            let err = &target.data - &self.forward(inp);
+            inp.data.transpose().matmul(&err)
+        }
+
+        fn params(&self) -> &Matrix {
+            &self.weights
+        }
+
+        fn params_mut(&mut self) -> &mut Matrix {
+            &mut self.weights
+        }
+
+        fn update(&mut self, derivatives: Matrix) {
+            self.weights += &derivatives.apply(|v| v * 0.001);
+        }
+    }
+
+    #[test]
+    fn test_custom_params() {
+        let result = check_edge(1e-6, |rng| {
+            let inp = GaussianVariable::new(Matrix::normal_with_rng(4, 3, 0.0, 1.0, rng), false);
+            let target = GaussianVariable::new(Matrix::normal_with_rng(4, 5, 0.0, 1.0, rng), true);
+            let weights = Matrix::normal_with_rng(3, 5, 0.0, 0.5, rng);
+            (inp, target, Projection { weights })
+        });
+        assert!(result.max() < 1e-5, "{:?}", result);
+    }
+}
@@ -0,0 +1,111 @@
+use std::marker::PhantomData;
+
+use crate::graph::Variable;
+use crate::linalg::matrix::Matrix;
+
+// The interval a `BoundedVariable` keeps its state in.
+pub trait Support: Send + Sync {
+    const LOW: f64;
+    const HIGH: f64;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Real;
+
+impl Support for Real {
+    const LOW: f64 = f64::NEG_INFINITY;
+    const HIGH: f64 = f64::INFINITY;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NonNegative;
+
+impl Support for NonNegative {
+    const LOW: f64 = 0.0;
+    const HIGH: f64 = f64::INFINITY;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UnitInterval;
+
+impl Support for UnitInterval {
+    const LOW: f64 = 0.0;
+    const HIGH: f64 = 1.0;
+}
+
+// A plain node for non-Gaussian likelihoods: gradient steps on its data, projected
+// back onto the support `S` after every update.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BoundedVariable<S: Support> {
+    pub size: usize,
+    pub data: Matrix,
+    fixed: bool,
+    support: PhantomData<S>,
+}
+
+impl<S: Support> BoundedVariable<S> {
+    pub fn new(data: Matrix, fixed: bool) -> Self {
+        assert!(data
+            .data
+            .iter()
+            .flatten()
+            .all(|v| (S::LOW..=S::HIGH).contains(v)));
+        let size = data.cols;
+        Self {
+            size,
+            data,
+            fixed,
+            support: PhantomData,
+        }
+    }
+
+    pub fn set_data(&mut self, data: Matrix) {
+        assert_eq!(data.cols, self.size);
+        self.data = data;
+    }
+}
+
+impl<S: Support> Variable for BoundedVariable<S> {
+    type Gradient = Matrix;
+
+    fn data(&self) -> &Matrix {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut Matrix {
+        &mut self.data
+    }
+
+    fn fixed(&self) -> bool {
+        self.fixed
+    }
+
+    fn set_fixed(&mut self, fixed: bool) {
+        self.fixed = fixed;
+    }
+
+    fn update(&mut self, derivatives: &[Matrix], rate: f64) {
+        if !self.fixed {
+            for derivative in derivatives {
+                self.data += &derivative.apply(|v| v * rate);
+            }
+            self.data = self.data.apply(|v| v.clamp(S::LOW, S::HIGH));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_update_stays_in_support() {
+        let mut variable = BoundedVariable::<UnitInterval>::new(Matrix::ones(1, 2) * 0.5, false);
+        variable.update(&[Matrix::new(vec![vec![1000.0, -1000.0]])], 0.001);
+        assert_eq!(variable.data, Matrix::new(vec![vec![1.0, 0.0]]));
+
+        let mut variable = BoundedVariable::<Real>::new(Matrix::zeros(1, 1), false);
+        variable.update(&[Matrix::ones(1, 1) * -1000.0], 0.001);
+        assert_eq!(variable.data, Matrix::ones(1, 1) * -1.0);
+    }
+}
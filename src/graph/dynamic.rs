@@ -0,0 +1,223 @@
+use std::any::Any;
+
+use crate::linalg::matrix::Matrix;
+
+use super::{params::Tensors, Function, Graph, Variable};
+
+// Boxed nodes and edges let a single graph mix likelihoods, e.g. Gaussian latents
+// predicting Bernoulli pixels and categorical labels. Edge functions are wrapped by
+// `boxed`, which erases their parameter and gradient types.
+pub type DynVariable = Box<dyn Variable<Gradient = Matrix>>;
+pub type DynGradient = Box<dyn AnyTensors>;
+pub type DynFunction = Box<dyn Function<DynVariable, Params = dyn Tensors, Gradient = DynGradient>>;
+pub type DynGraph = Graph<DynVariable, DynFunction>;
+
+pub trait AnyTensors: Tensors + Send {
+    fn into_any(self: Box<Self>) -> Box<dyn Any>;
+}
+
+impl<G: Tensors + Send + 'static> AnyTensors for G {
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+}
+
+impl Tensors for Box<dyn AnyTensors> {
+    fn flatten(&self) -> Vec<f64> {
+        self.as_ref().flatten()
+    }
+
+    fn flatten_mut(&mut self) -> Vec<&mut f64> {
+        self.as_mut().flatten_mut()
+    }
+
+    fn named(&self) -> Vec<(&'static str, &dyn Tensors)> {
+        self.as_ref().named()
+    }
+
+    fn named_mut(&mut self) -> Vec<(&'static str, &mut dyn Tensors)> {
+        self.as_mut().named_mut()
+    }
+}
+
+pub struct Erased<F>(F);
+
+pub fn boxed<T, F>(
+    function: F,
+) -> Box<dyn Function<T, Params = dyn Tensors, Gradient = DynGradient>>
+where
+    T: Variable,
+    F: Function<T> + 'static,
+    F::Params: Sized,
+    F::Gradient: 'static,
+{
+    Box::new(Erased(function))
+}
+
+impl<T, F> Function<T> for Erased<F>
+where
+    T: Variable,
+    F: Function<T>,
+    F::Params: Sized,
+    F::Gradient: 'static,
+{
+    type Params = dyn Tensors;
+    type Gradient = DynGradient;
+
+    fn forward(&self, input: &T) -> Matrix {
+        self.0.forward(input)
+    }
+
+    fn energy(&self, input: &T, target: &T) -> f64 {
+        self.0.energy(input, target)
+    }
+
+    fn backward(&self, input: &T, target: &T) -> (T::Gradient, T::Gradient) {
+        self.0.backward(input, target)
+    }
+
+    fn backward_params(&self, input: &T, target: &T) -> DynGradient {
+        Box::new(self.0.backward_params(input, target))
+    }
+
+    fn params(&self) -> &Self::Params {
+        self.0.params()
+    }
+
+    fn params_mut(&mut self) -> &mut Self::Params {
+        self.0.params_mut()
+    }
+
+    fn update(&mut self, derivatives: DynGradient) {
+        let derivatives = derivatives
+            .into_any()
+            .downcast::<F::Gradient>()
+            .expect("gradient does not belong to this function");
+        self.0.update(*derivatives)
+    }
+}
+
+impl<G: Send> Variable for Box<dyn Variable<Gradient = G>> {
+    type Gradient = G;
+
+    fn data(&self) -> &Matrix {
+        self.as_ref().data()
+    }
+
+    fn data_mut(&mut self) -> &mut Matrix {
+        self.as_mut().data_mut()
+    }
+
+    fn fixed(&self) -> bool {
+        self.as_ref().fixed()
+    }
+
+    fn set_fixed(&mut self, fixed: bool) {
+        self.as_mut().set_fixed(fixed)
+    }
+
+    fn update(&mut self, derivatives: &[G], rate: f64) {
+        self.as_mut().update(derivatives, rate)
+    }
+
+    fn energy(&self) -> f64 {
+        self.as_ref().energy()
+    }
+
+    fn backward(&self) -> Option<G> {
+        self.as_ref().backward()
+    }
+
+    fn learn(&mut self) {
+        self.as_mut().learn()
+    }
+
+    fn parameters(&self) -> Vec<(&'static str, &dyn Tensors)> {
+        self.as_ref().parameters()
+    }
+
+    fn parameters_mut(&mut self) -> Vec<(&'static str, &mut dyn Tensors)> {
+        self.as_mut().parameters_mut()
+    }
+}
+
+impl<T, P, G> Function<T> for Box<dyn Function<T, Params = P, Gradient = G>>
+where
+    T: Variable,
+    P: Tensors + ?Sized + 'static,
+    G: Tensors + Send,
+{
+    type Params = P;
+    type Gradient = G;
+
+    fn forward(&self, input: &T) -> Matrix {
+        self.as_ref().forward(input)
+    }
+
+    fn energy(&self, input: &T, target: &T) -> f64 {
+        self.as_ref().energy(input, target)
+    }
+
+    fn backward(&self, input: &T, target: &T) -> (T::Gradient, T::Gradient) {
+        self.as_ref().backward(input, target)
+    }
+
+    fn backward_params(&self, input: &T, target: &T) -> G {
+        self.as_ref().backward_params(input, target)
+    }
+
+    fn params(&self) -> &P {
+        self.as_ref().params()
+    }
+
+    fn params_mut(&mut self) -> &mut P {
+        self.as_mut().params_mut()
+    }
+
+    fn update(&mut self, derivatives: G) {
+        self.as_mut().update(derivatives)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bernoulli::{function::BernoulliFunction, variable::BernoulliVariable};
+    use crate::categorical::{function::CategoricalFunction, variable::CategoricalVariable};
+    use crate::gaussian::{function::GaussianFunction, variable::GaussianVariable};
+    use crate::linalg::{init::Init, math::Activation};
+
+    #[test]
+    fn test_mixed_likelihoods() {
+        let mut graph = DynGraph::with_seed(0);
+        let latent = Matrix::normal_with_rng(6, 4, 0.0, 0.1, graph.rng());
+        let pixels = Matrix::random_with_rng(6, 8, graph.rng()).apply(|v| v.round());
+        let latent = graph.add_node(Box::new(GaussianVariable::new(latent, false)));
+        let data = graph.add_node(Box::new(GaussianVariable::new(Matrix::ones(6, 3), true)));
+        let pixels = graph.add_node(Box::new(BernoulliVariable::new(pixels, true)));
+        let labels = CategoricalVariable::from_labels(&[0, 1, 2, 0, 1, 2], 3, true);
+        let labels = graph.add_node(Box::new(labels));
+
+        let init = Init::XavierNormal;
+        let gaussian =
+            GaussianFunction::from_init_with_rng(4, 3, init, Activation::Tanh, graph.rng());
+        let bernoulli = BernoulliFunction::from_init_with_rng(4, 8, init, graph.rng());
+        let categorical = CategoricalFunction::from_init_with_rng(4, 3, init, graph.rng());
+        graph.add_edge(latent, data, boxed(gaussian));
+        graph.add_edge(latent, pixels, boxed(bernoulli));
+        graph.add_edge(latent, labels, boxed(categorical));
+
+        let initial = graph.energy();
+        for _ in 0..20 {
+            for _ in 0..20 {
+                graph.infer();
+            }
+            graph.learn();
+        }
+        assert!(graph.energy() < initial);
+
+        let preds = graph.forward();
+        assert_eq!(preds[&(latent, pixels)].cols, 8);
+        assert_eq!(preds[&(latent, labels)].cols, 3);
+    }
+}
@@ -1,16 +1,73 @@
+pub mod bounded;
+pub mod dynamic;
+pub mod mode;
+pub mod order;
+pub mod params;
+pub mod schedule;
+
 use std::collections::HashMap;
 
-use crate::linalg::{matrix::Matrix, vector::Vector};
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+use rayon::prelude::*;
+
+use crate::linalg::matrix::Matrix;
+
+use self::{mode::Mode, params::Tensors, schedule::Schedule};
+
+pub trait Function<T: Variable>: Send + Sync {
+    type Params: Tensors + ?Sized + 'static;
+    type Gradient: Tensors + Send;
 
-pub trait Function<T: Variable> {
     fn forward(&self, input: &T) -> Matrix;
-    fn backward(&self, input: &T, target: &T) -> (Matrix, Matrix);
-    fn backward_params(&self, input: &T, target: &T) -> (Matrix, Vector);
-    fn update(&mut self, derivatives: (Matrix, Vector));
+    fn energy(&self, input: &T, target: &T) -> f64;
+    fn backward(&self, input: &T, target: &T) -> (T::Gradient, T::Gradient);
+    fn backward_params(&self, input: &T, target: &T) -> Self::Gradient;
+    fn params(&self) -> &Self::Params;
+    fn params_mut(&mut self) -> &mut Self::Params;
+    fn update(&mut self, derivatives: Self::Gradient);
+
+    fn parameters(&self) -> Vec<(&'static str, &dyn Tensors)> {
+        self.params().named()
+    }
+
+    fn parameters_mut(&mut self) -> Vec<(&'static str, &mut dyn Tensors)> {
+        self.params_mut().named_mut()
+    }
+
+    fn num_parameters(&self) -> usize {
+        self.params().flatten().len()
+    }
 }
 
-pub trait Variable {
-    fn update(&mut self, derivatives: &Vec<Matrix>);
+pub trait Variable: Send + Sync {
+    type Gradient: Send;
+
+    fn data(&self) -> &Matrix;
+    fn data_mut(&mut self) -> &mut Matrix;
+    fn fixed(&self) -> bool;
+    fn set_fixed(&mut self, fixed: bool);
+    // Steps the node's state along `derivatives`, scaled by the graph's inference rate.
+    fn update(&mut self, derivatives: &[Self::Gradient], rate: f64);
+
+    // Energy of any term owned by the node itself, such as a prior.
+    fn energy(&self) -> f64 {
+        0.0
+    }
+
+    fn backward(&self) -> Option<Self::Gradient> {
+        None
+    }
+
+    fn learn(&mut self) {}
+
+    // Tensors owned by the node itself, such as the parameters of a prior.
+    fn parameters(&self) -> Vec<(&'static str, &dyn Tensors)> {
+        Vec::new()
+    }
+
+    fn parameters_mut(&mut self) -> Vec<(&'static str, &mut dyn Tensors)> {
+        Vec::new()
+    }
 }
 
 pub struct Edge<F> {
@@ -22,16 +79,114 @@ pub struct Edge<F> {
 pub struct Graph<T: Variable, F: Function<T>> {
     nodes: Vec<T>,
     edges: Vec<Edge<F>>,
+    rng: StdRng,
+    parallel: bool,
+    inference_rate: f64,
+    schedule: Schedule,
+    mode: Mode,
+    labels: Vec<usize>,
+}
+
+impl<T: Variable, F: Function<T>> Default for Graph<T, F> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl<T: Variable, F: Function<T>> Graph<T, F> {
     pub fn new() -> Self {
+        Self::with_rng(StdRng::from_entropy())
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
+        Self::with_rng(StdRng::seed_from_u64(seed))
+    }
+
+    pub fn with_rng(rng: StdRng) -> Self {
         Self {
             nodes: Vec::new(),
             edges: Vec::new(),
+            rng,
+            parallel: true,
+            inference_rate: 0.001,
+            schedule: Schedule::default(),
+            mode: Mode::default(),
+            labels: Vec::new(),
         }
     }
 
+    pub fn rng(&mut self) -> &mut StdRng {
+        &mut self.rng
+    }
+
+    // Inference and learning run edges and nodes on the rayon pool unless disabled.
+    pub fn set_parallel(&mut self, parallel: bool) {
+        self.parallel = parallel;
+    }
+
+    pub fn parallel(&self) -> bool {
+        self.parallel
+    }
+
+    // Step size of every node update in `infer`.
+    pub fn set_inference_rate(&mut self, inference_rate: f64) {
+        self.inference_rate = inference_rate;
+    }
+
+    pub fn inference_rate(&self) -> f64 {
+        self.inference_rate
+    }
+
+    pub fn set_schedule(&mut self, schedule: Schedule) {
+        self.schedule = schedule;
+    }
+
+    pub fn schedule(&self) -> Schedule {
+        self.schedule
+    }
+
+    pub fn clamp(&mut self, node: usize) {
+        self.nodes[node].set_fixed(true);
+    }
+
+    pub fn unclamp(&mut self, node: usize) {
+        self.nodes[node].set_fixed(false);
+    }
+
+    // Label nodes are clamped in `Mode::Train` and left free to be inferred in
+    // `Mode::Eval`.
+    pub fn set_labels(&mut self, labels: &[usize]) {
+        self.labels = labels.to_vec();
+        self.set_mode(self.mode);
+    }
+
+    pub fn labels(&self) -> &[usize] {
+        &self.labels
+    }
+
+    pub fn set_mode(&mut self, mode: Mode) {
+        self.mode = mode;
+        for i in 0..self.labels.len() {
+            let node = self.labels[i];
+            match mode {
+                Mode::Train => self.clamp(node),
+                Mode::Eval => self.unclamp(node),
+            }
+        }
+    }
+
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    pub fn train(&mut self) {
+        self.set_mode(Mode::Train);
+    }
+
+    pub fn eval(&mut self) {
+        self.set_mode(Mode::Eval);
+    }
+
     pub fn forward(&mut self) -> HashMap<(usize, usize), Matrix> {
         let mut preds: HashMap<(usize, usize), Matrix> = HashMap::new();
 
@@ -42,39 +197,228 @@ impl<T: Variable, F: Function<T>> Graph<T, F> {
             preds.insert((edge.source, edge.target), pred);
         }
 
-        return preds;
+        preds
+    }
+
+    // Sets every non-fixed node reachable from `root_nodes` to the mean of its
+    // incoming predictions, visiting nodes in topological order. Nodes that are not
+    // reachable keep their data.
+    pub fn forward_init(&mut self, root_nodes: &[usize]) {
+        let mut initialised = vec![false; self.nodes.len()];
+        for &index in root_nodes {
+            initialised[index] = true;
+        }
+
+        for index in self.sweep_order() {
+            if initialised[index] {
+                continue;
+            }
+
+            let mut preds = self
+                .edges
+                .iter()
+                .filter(|edge| edge.target == index && initialised[edge.source])
+                .map(|edge| edge.function.forward(&self.nodes[edge.source]));
+            let mut mean = match preds.next() {
+                Some(pred) => pred,
+                None => continue,
+            };
+            let mut count = 1.0;
+            for pred in preds {
+                mean = &mean + &pred;
+                count += 1.0;
+            }
+
+            let node = &mut self.nodes[index];
+            if !node.fixed() {
+                *node.data_mut() = mean.apply(|v| v / count);
+            }
+            initialised[index] = true;
+        }
+    }
+
+    pub fn energy(&self) -> f64 {
+        let node_energy: f64 = self.get_nodes().map(|node| node.energy()).sum();
+        let edge_energy: f64 = self
+            .get_edges()
+            .map(|edge| {
+                let source = self.get_node(edge.source).unwrap();
+                let target = self.get_node(edge.target).unwrap();
+                edge.function.energy(source, target)
+            })
+            .sum();
+        node_energy + edge_energy
     }
 
     pub fn infer(&mut self) {
-        let mut deltas: HashMap<usize, Vec<Matrix>> = HashMap::new();
-        for (node_index, _) in self.get_nodes().enumerate() {
-            deltas.insert(node_index, Vec::new());
+        match self.schedule {
+            Schedule::Synchronous => self.infer_synchronous(),
+            Schedule::Topological => {
+                let order = self.sweep_order();
+                self.infer_sequential(&order);
+            }
+            Schedule::Reverse => {
+                let mut order = self.sweep_order();
+                order.reverse();
+                self.infer_sequential(&order);
+            }
+            Schedule::Random => {
+                let mut order: Vec<usize> = (0..self.nodes.len()).collect();
+                order.shuffle(&mut self.rng);
+                self.infer_sequential(&order);
+            }
         }
+    }
 
-        for edge in self.get_edges() {
-            let source = &self.get_node(edge.source).unwrap();
-            let target = &self.get_node(edge.target).unwrap();
-            let function = &edge.function;
-            let (source_deriv, target_deriv) = function.backward(source, target);
+    fn infer_synchronous(&mut self) {
+        let nodes = &self.nodes;
+        let backward = |edge: &Edge<F>| {
+            let source = &nodes[edge.source];
+            let target = &nodes[edge.target];
+            edge.function.backward(source, target)
+        };
+        let edge_derivs: Vec<(T::Gradient, T::Gradient)> = if self.parallel {
+            self.edges.par_iter().map(backward).collect()
+        } else {
+            self.edges.iter().map(backward).collect()
+        };
 
-            deltas.get_mut(&edge.target).unwrap().push(target_deriv);
-            deltas.get_mut(&edge.source).unwrap().push(source_deriv);
+        // Deltas are gathered in edge order regardless of scheduling, so every node
+        // sees its terms in the same order as the serial path.
+        let mut deltas: Vec<Vec<T::Gradient>> = self
+            .nodes
+            .iter()
+            .map(|node| node.backward().into_iter().collect())
+            .collect();
+        for (edge, (source_deriv, target_deriv)) in self.edges.iter().zip(edge_derivs) {
+            deltas[edge.target].push(target_deriv);
+            deltas[edge.source].push(source_deriv);
         }
 
-        for (node_index, deltas) in deltas.iter_mut() {
-            let node = self.get_node_mut(*node_index).unwrap();
-            node.update(deltas);
+        let rate = self.inference_rate;
+        if self.parallel {
+            self.nodes
+                .par_iter_mut()
+                .zip(deltas.into_par_iter())
+                .for_each(|(node, deltas)| node.update(&deltas, rate));
+        } else {
+            for (node, deltas) in self.nodes.iter_mut().zip(deltas) {
+                node.update(&deltas, rate);
+            }
         }
     }
 
+    // Updates one node at a time, recomputing its deltas from the latest state of
+    // its neighbours. Deltas are gathered in the same order as the synchronous path.
+    fn infer_sequential(&mut self, order: &[usize]) {
+        for &index in order {
+            let nodes = &self.nodes;
+            let backward = |edge: &Edge<F>| {
+                let source = &nodes[edge.source];
+                let target = &nodes[edge.target];
+                let (source_deriv, target_deriv) = edge.function.backward(source, target);
+                let mut derivs = Vec::new();
+                if edge.target == index {
+                    derivs.push(target_deriv);
+                }
+                if edge.source == index {
+                    derivs.push(source_deriv);
+                }
+                derivs
+            };
+            let incident = self
+                .edges
+                .iter()
+                .filter(|edge| edge.source == index || edge.target == index);
+            let edge_derivs: Vec<Vec<T::Gradient>> = if self.parallel {
+                incident
+                    .collect::<Vec<_>>()
+                    .into_par_iter()
+                    .map(backward)
+                    .collect()
+            } else {
+                incident.map(backward).collect()
+            };
+
+            let mut deltas: Vec<T::Gradient> = nodes[index].backward().into_iter().collect();
+            deltas.extend(edge_derivs.into_iter().flatten());
+            self.nodes[index].update(&deltas, self.inference_rate);
+        }
+    }
+
+    // Parameters are frozen in `Mode::Eval`.
     pub fn learn(&mut self) {
-        for i in 0..self.edges.len() {
-            let source = &self.get_node(self.edges[i].source).unwrap();
-            let target = &self.get_node(self.edges[i].target).unwrap();
+        if self.mode == Mode::Eval {
+            return;
+        }
+
+        if self.parallel {
+            self.nodes.par_iter_mut().for_each(|node| node.learn());
+        } else {
+            self.nodes.iter_mut().for_each(|node| node.learn());
+        }
+
+        let nodes = &self.nodes;
+        let learn = |edge: &mut Edge<F>| {
+            let source = &nodes[edge.source];
+            let target = &nodes[edge.target];
+            let derivative = edge.function.backward_params(source, target);
+            edge.function.update(derivative);
+        };
+        if self.parallel {
+            self.edges.par_iter_mut().for_each(learn);
+        } else {
+            self.edges.iter_mut().for_each(learn);
+        }
+    }
 
-            let derivative = self.edges[i].function.backward_params(source, target);
-            self.edges[i].function.update(derivative);
+    // Edge tensors are named `<edge>.<name>`, or just `<edge>` when a function's
+    // parameters are a single tensor, followed by node tensors named
+    // `node.<node>.<name>`. Empty tensors, such as the precision of an identity
+    // covariance, are left out.
+    pub fn parameters(&self) -> Vec<(String, &dyn Tensors)> {
+        let mut parameters = Vec::new();
+        for (index, edge) in self.edges.iter().enumerate() {
+            for (name, tensor) in edge.function.parameters() {
+                if !tensor.flatten().is_empty() {
+                    parameters.push((parameter_name(index, name), tensor));
+                }
+            }
+        }
+        for (index, node) in self.nodes.iter().enumerate() {
+            for (name, tensor) in node.parameters() {
+                if !tensor.flatten().is_empty() {
+                    parameters.push((node_parameter_name(index, name), tensor));
+                }
+            }
         }
+        parameters
+    }
+
+    pub fn parameters_mut(&mut self) -> Vec<(String, &mut dyn Tensors)> {
+        let mut parameters = Vec::new();
+        for (index, edge) in self.edges.iter_mut().enumerate() {
+            for (name, tensor) in edge.function.parameters_mut() {
+                if !tensor.flatten_mut().is_empty() {
+                    parameters.push((parameter_name(index, name), tensor));
+                }
+            }
+        }
+        for (index, node) in self.nodes.iter_mut().enumerate() {
+            for (name, tensor) in node.parameters_mut() {
+                if !tensor.flatten_mut().is_empty() {
+                    parameters.push((node_parameter_name(index, name), tensor));
+                }
+            }
+        }
+        parameters
+    }
+
+    pub fn num_parameters(&self) -> usize {
+        self.parameters()
+            .iter()
+            .map(|(_, tensor)| tensor.flatten().len())
+            .sum()
     }
 
     pub fn add_node(&mut self, value: T) -> usize {
@@ -108,11 +452,267 @@ impl<T: Variable, F: Function<T>> Graph<T, F> {
         self.nodes.get_mut(index)
     }
 
-    pub fn get_nodes(&self) -> std::slice::Iter<T> {
+    pub fn get_nodes(&self) -> std::slice::Iter<'_, T> {
         self.nodes.iter()
     }
 
-    pub fn get_edges(&self) -> std::slice::Iter<Edge<F>> {
+    pub fn get_edges(&self) -> std::slice::Iter<'_, Edge<F>> {
         self.edges.iter()
     }
 }
+
+fn parameter_name(index: usize, name: &str) -> String {
+    if name.is_empty() {
+        index.to_string()
+    } else {
+        format!("{}.{}", index, name)
+    }
+}
+
+fn node_parameter_name(index: usize, name: &str) -> String {
+    format!("node.{}", parameter_name(index, name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gaussian::{function::GaussianFunction, variable::GaussianVariable};
+    use crate::linalg::{math::Activation, vector::Vector};
+
+    fn train(seed: u64) -> Vec<Matrix> {
+        let mut graph = Graph::<GaussianVariable, GaussianFunction>::with_seed(seed);
+        let mu = Matrix::normal_with_rng(4, 3, 0.0, 0.05, graph.rng());
+        let weights = Matrix::normal_with_rng(3, 5, 0.0, 0.05, graph.rng());
+        let mu_index = graph.add_node(GaussianVariable::new(mu, false));
+        let data_index = graph.add_node(GaussianVariable::new(Matrix::ones(4, 5), true));
+        let function = GaussianFunction::new(weights, Vector::zeros(5), Activation::Linear);
+        graph.add_edge(mu_index, data_index, function);
+
+        for _ in 0..3 {
+            let mu = Matrix::normal_with_rng(4, 3, 0.0, 0.05, graph.rng());
+            graph.get_node_mut(mu_index).unwrap().set_data(mu);
+            for _ in 0..5 {
+                graph.infer();
+            }
+            graph.learn();
+        }
+
+        graph
+            .get_edges()
+            .map(|edge| edge.function.weights().clone())
+            .collect()
+    }
+
+    // `width` latent nodes each predict both a hidden and an observed node.
+    fn train_wide(seed: u64, width: usize, parallel: bool) -> Vec<Matrix> {
+        let mut graph = Graph::<GaussianVariable, GaussianFunction>::with_seed(seed);
+        graph.set_parallel(parallel);
+        let data_index = graph.add_node(GaussianVariable::new(Matrix::ones(4, 5), true));
+        let mut mu_indices = Vec::new();
+        for _ in 0..width {
+            let mu = Matrix::normal_with_rng(4, 3, 0.0, 0.05, graph.rng());
+            let weights = Matrix::normal_with_rng(3, 5, 0.0, 0.05, graph.rng());
+            let mu_index = graph.add_node(GaussianVariable::new(mu, false));
+            let function = GaussianFunction::new(weights, Vector::zeros(5), Activation::Tanh);
+            graph.add_edge(mu_index, data_index, function);
+            mu_indices.push(mu_index);
+        }
+        if width > 1 {
+            let hidden = Matrix::zeros(4, 3);
+            let hidden_index = graph.add_node(GaussianVariable::new(hidden, false));
+            for &mu_index in mu_indices.iter() {
+                let weights = Matrix::normal_with_rng(3, 3, 0.0, 0.05, graph.rng());
+                let function = GaussianFunction::new(weights, Vector::zeros(3), Activation::Tanh);
+                graph.add_edge(mu_index, hidden_index, function);
+            }
+        }
+
+        for _ in 0..3 {
+            for &mu_index in mu_indices.iter() {
+                let mu = Matrix::normal_with_rng(4, 3, 0.0, 0.05, graph.rng());
+                graph.get_node_mut(mu_index).unwrap().set_data(mu);
+            }
+            for _ in 0..5 {
+                graph.infer();
+            }
+            graph.learn();
+        }
+
+        graph
+            .get_edges()
+            .map(|edge| edge.function.weights().clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_parameters() {
+        let mut graph = Graph::<GaussianVariable, GaussianFunction>::with_seed(0);
+        let a = graph.add_node(GaussianVariable::new(Matrix::ones(2, 3), false));
+        let b = graph.add_node(GaussianVariable::new(Matrix::ones(2, 4), true));
+        let weights = Matrix::normal_with_rng(3, 4, 0.0, 1.0, graph.rng());
+        let function = GaussianFunction::new(weights, Vector::zeros(4), Activation::Linear)
+            .with_scalar_precision(1.0);
+        graph.add_edge(a, b, function);
+        let weights = Matrix::normal_with_rng(3, 4, 0.0, 1.0, graph.rng());
+        let function = GaussianFunction::new(weights, Vector::zeros(4), Activation::Linear);
+        graph.add_edge(a, b, function);
+
+        let names: Vec<String> = graph
+            .parameters()
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+        assert_eq!(
+            names,
+            vec!["0.weights", "0.bias", "0.precision", "1.weights", "1.bias"]
+        );
+        assert_eq!(graph.num_parameters(), 2 * (3 * 4 + 4) + 1);
+        assert_eq!(parameter_name(2, ""), "2");
+
+        for (name, tensor) in graph.parameters_mut() {
+            if name.ends_with("weights") {
+                tensor.flatten_mut().into_iter().for_each(|v| *v = 0.0);
+            }
+        }
+        let preds = graph.forward();
+        assert_eq!(preds[&(a, b)], Matrix::zeros(2, 4));
+    }
+
+    #[test]
+    fn test_same_seed_is_reproducible() {
+        let a = train(42);
+        let b = train(42);
+        let c = train(43);
+        for (a, b) in a.iter().zip(b.iter()) {
+            assert_eq!(a.data, b.data);
+        }
+        assert_ne!(a[0].data, c[0].data);
+    }
+
+    #[test]
+    fn test_parallel_matches_serial() {
+        let parallel = train_wide(7, 16, true);
+        let serial = train_wide(7, 16, false);
+        assert_eq!(parallel.len(), serial.len());
+        for (a, b) in parallel.iter().zip(serial.iter()) {
+            assert_eq!(a.data, b.data);
+        }
+    }
+
+    // x -> h1 -> h2 -> y with both ends clamped.
+    fn chain(schedule: Schedule) -> Graph<GaussianVariable, GaussianFunction> {
+        let mut graph = Graph::with_seed(0);
+        graph.set_schedule(schedule);
+        let sizes = [3, 4, 4, 2];
+        let mut indices = Vec::new();
+        for (i, &size) in sizes.iter().enumerate() {
+            let fixed = i == 0 || i == sizes.len() - 1;
+            let data = Matrix::normal_with_rng(5, size, 0.0, 1.0, graph.rng());
+            indices.push(graph.add_node(GaussianVariable::new(data, fixed)));
+        }
+        for i in 0..sizes.len() - 1 {
+            let weights = Matrix::normal_with_rng(sizes[i], sizes[i + 1], 0.0, 0.5, graph.rng());
+            let function =
+                GaussianFunction::new(weights, Vector::zeros(sizes[i + 1]), Activation::Tanh);
+            graph.add_edge(indices[i], indices[i + 1], function);
+        }
+        graph
+    }
+
+    #[test]
+    fn test_schedules_reduce_energy() {
+        let schedules = [
+            Schedule::Synchronous,
+            Schedule::Topological,
+            Schedule::Reverse,
+            Schedule::Random,
+        ];
+        for &schedule in schedules.iter() {
+            let mut graph = chain(schedule);
+            let initial = graph.energy();
+            for _ in 0..50 {
+                graph.infer();
+            }
+            assert!(graph.energy() < initial, "{:?}", schedule);
+        }
+    }
+
+    #[test]
+    fn test_sequential_schedules_see_updated_neighbours() {
+        let mut synchronous = chain(Schedule::Synchronous);
+        let mut topological = chain(Schedule::Topological);
+        let mut reverse = chain(Schedule::Reverse);
+        synchronous.infer();
+        topological.infer();
+        reverse.infer();
+
+        // h1 is swept first in topological order, so only h2 can differ there.
+        assert_eq!(synchronous.nodes[1].data, topological.nodes[1].data);
+        assert_ne!(synchronous.nodes[2].data, topological.nodes[2].data);
+        assert_eq!(synchronous.nodes[2].data, reverse.nodes[2].data);
+        assert_ne!(synchronous.nodes[1].data, reverse.nodes[1].data);
+    }
+
+    #[test]
+    fn test_sequential_parallel_matches_serial() {
+        let mut parallel = chain(Schedule::Random);
+        let mut serial = chain(Schedule::Random);
+        serial.set_parallel(false);
+        for _ in 0..10 {
+            parallel.infer();
+            serial.infer();
+        }
+        for (a, b) in parallel.get_nodes().zip(serial.get_nodes()) {
+            assert_eq!(a.data.data, b.data.data);
+        }
+    }
+
+    #[test]
+    fn test_forward_init() {
+        let mut graph = chain(Schedule::Synchronous);
+        let target = graph.nodes[3].data.clone();
+        let initial = graph.energy();
+        graph.forward_init(&[0]);
+
+        let preds = graph.forward();
+        assert_eq!(graph.nodes[1].data, preds[&(0, 1)]);
+        assert_eq!(graph.nodes[2].data, preds[&(1, 2)]);
+        assert_eq!(graph.nodes[3].data, target);
+
+        // Only the prediction of the clamped output is left with any error.
+        let output_energy = graph.edges[2]
+            .function
+            .energy(&graph.nodes[2], &graph.nodes[3]);
+        assert!((graph.energy() - output_energy).abs() < 1e-9);
+        assert!(graph.energy() < initial);
+    }
+
+    #[test]
+    fn test_train_eval_modes() {
+        let mut graph = chain(Schedule::Synchronous);
+        graph.set_labels(&[3]);
+        assert_eq!(graph.mode(), Mode::Train);
+        assert!(graph.nodes[3].fixed());
+
+        let label = graph.nodes[3].data.clone();
+        graph.infer();
+        assert_eq!(graph.nodes[3].data, label);
+
+        graph.eval();
+        assert!(!graph.nodes[3].fixed());
+        let weights = graph.edges[2].function.weights().clone();
+        let initial = graph.energy();
+        graph.infer();
+        graph.learn();
+        assert_ne!(graph.nodes[3].data, label);
+        assert_eq!(graph.edges[2].function.weights(), &weights);
+        assert!(graph.energy() < initial);
+
+        graph.train();
+        assert!(graph.nodes[3].fixed());
+        graph.unclamp(0);
+        assert!(!graph.nodes[0].fixed());
+        graph.clamp(0);
+        assert!(graph.nodes[0].fixed());
+    }
+}
@@ -0,0 +1,8 @@
+// Whether the graph is being trained or evaluated. Switching mode clamps or unclamps
+// the graph's label nodes, and `Graph::learn` leaves parameters untouched in `Eval`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Mode {
+    #[default]
+    Train,
+    Eval,
+}
@@ -0,0 +1,190 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::error::Error;
+use std::fmt;
+
+use super::{Function, Graph, Variable};
+
+// Returned when the graph is not a DAG. `nodes` holds every node that could not be
+// ordered, i.e. the nodes on a cycle and anything downstream of one.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CycleError {
+    pub nodes: Vec<usize>,
+}
+
+impl fmt::Display for CycleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "graph contains a cycle through nodes {:?}", self.nodes)
+    }
+}
+
+impl Error for CycleError {}
+
+impl<T: Variable, F: Function<T>> Graph<T, F> {
+    pub fn in_degree(&self, node: usize) -> usize {
+        self.edges.iter().filter(|edge| edge.target == node).count()
+    }
+
+    pub fn out_degree(&self, node: usize) -> usize {
+        self.edges.iter().filter(|edge| edge.source == node).count()
+    }
+
+    // Distinct source nodes of the edges into `node`, in edge order.
+    pub fn parents(&self, node: usize) -> Vec<usize> {
+        let mut parents = Vec::new();
+        for edge in self.edges.iter().filter(|edge| edge.target == node) {
+            if !parents.contains(&edge.source) {
+                parents.push(edge.source);
+            }
+        }
+        parents
+    }
+
+    pub fn children(&self, node: usize) -> Vec<usize> {
+        let mut children = Vec::new();
+        for edge in self.edges.iter().filter(|edge| edge.source == node) {
+            if !children.contains(&edge.target) {
+                children.push(edge.target);
+            }
+        }
+        children
+    }
+
+    pub fn roots(&self) -> Vec<usize> {
+        (0..self.nodes.len())
+            .filter(|&node| self.in_degree(node) == 0)
+            .collect()
+    }
+
+    pub fn leaves(&self) -> Vec<usize> {
+        (0..self.nodes.len())
+            .filter(|&node| self.out_degree(node) == 0)
+            .collect()
+    }
+
+    // Kahn's algorithm, breaking ties by node index.
+    pub fn topological_order(&self) -> Result<Vec<usize>, CycleError> {
+        let (order, remaining) = self.partial_order();
+        if remaining.is_empty() {
+            Ok(order)
+        } else {
+            Err(CycleError { nodes: remaining })
+        }
+    }
+
+    // Length of the longest path from any root to each node.
+    pub fn depths(&self) -> Result<Vec<usize>, CycleError> {
+        let order = self.topological_order()?;
+        let mut depths = vec![0; self.nodes.len()];
+        for node in order {
+            for edge in self.edges.iter().filter(|edge| edge.source == node) {
+                depths[edge.target] = depths[edge.target].max(depths[node] + 1);
+            }
+        }
+        Ok(depths)
+    }
+
+    // Nodes grouped by depth, so that every edge points to a later layer.
+    pub fn layers(&self) -> Result<Vec<Vec<usize>>, CycleError> {
+        let depths = self.depths()?;
+        let num_layers = depths.iter().max().map_or(0, |depth| depth + 1);
+        let mut layers = vec![Vec::new(); num_layers];
+        for (node, &depth) in depths.iter().enumerate() {
+            layers[depth].push(node);
+        }
+        Ok(layers)
+    }
+
+    // Topological order with any nodes left on a cycle appended in index order, for
+    // sweeps that have to visit every node.
+    pub(crate) fn sweep_order(&self) -> Vec<usize> {
+        let (mut order, remaining) = self.partial_order();
+        order.extend(remaining);
+        order
+    }
+
+    fn partial_order(&self) -> (Vec<usize>, Vec<usize>) {
+        let mut in_degree = vec![0; self.nodes.len()];
+        for edge in self.edges.iter() {
+            in_degree[edge.target] += 1;
+        }
+
+        let mut ready: BinaryHeap<Reverse<usize>> = (0..self.nodes.len())
+            .filter(|&node| in_degree[node] == 0)
+            .map(Reverse)
+            .collect();
+        let mut order = Vec::with_capacity(self.nodes.len());
+        while let Some(Reverse(node)) = ready.pop() {
+            order.push(node);
+            for edge in self.edges.iter().filter(|edge| edge.source == node) {
+                in_degree[edge.target] -= 1;
+                if in_degree[edge.target] == 0 {
+                    ready.push(Reverse(edge.target));
+                }
+            }
+        }
+
+        let remaining = (0..self.nodes.len())
+            .filter(|&node| in_degree[node] > 0)
+            .collect();
+        (order, remaining)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gaussian::{function::GaussianFunction, variable::GaussianVariable};
+    use crate::linalg::{math::Activation, matrix::Matrix, vector::Vector};
+
+    fn graph(edges: &[(usize, usize)]) -> Graph<GaussianVariable, GaussianFunction> {
+        let mut graph = Graph::with_seed(0);
+        let num_nodes = edges.iter().map(|&(s, t)| s.max(t) + 1).max().unwrap_or(0);
+        for _ in 0..num_nodes {
+            graph.add_node(GaussianVariable::new(Matrix::zeros(1, 2), false));
+        }
+        for &(source, target) in edges {
+            let function =
+                GaussianFunction::new(Matrix::zeros(2, 2), Vector::zeros(2), Activation::Linear);
+            graph.add_edge(source, target, function);
+        }
+        graph
+    }
+
+    #[test]
+    fn test_topology() {
+        // 0 -> 2, 1 -> 2, 2 -> 3, 0 -> 3, 3 -> 4, 2 -> 4
+        let graph = graph(&[(0, 2), (1, 2), (2, 3), (0, 3), (3, 4), (2, 4)]);
+        assert_eq!(graph.parents(3), vec![2, 0]);
+        assert_eq!(graph.children(2), vec![3, 4]);
+        assert_eq!(graph.in_degree(2), 2);
+        assert_eq!(graph.out_degree(0), 2);
+        assert_eq!(graph.roots(), vec![0, 1]);
+        assert_eq!(graph.leaves(), vec![4]);
+        assert_eq!(graph.topological_order(), Ok(vec![0, 1, 2, 3, 4]));
+        assert_eq!(graph.depths(), Ok(vec![0, 0, 1, 2, 3]));
+        assert_eq!(
+            graph.layers(),
+            Ok(vec![vec![0, 1], vec![2], vec![3], vec![4]])
+        );
+    }
+
+    #[test]
+    fn test_cycle() {
+        // 0 -> 1 -> 2 -> 1, 2 -> 3
+        let graph = graph(&[(0, 1), (1, 2), (2, 1), (2, 3)]);
+        let error = CycleError {
+            nodes: vec![1, 2, 3],
+        };
+        assert_eq!(graph.topological_order(), Err(error.clone()));
+        assert_eq!(graph.layers(), Err(error));
+        assert_eq!(graph.sweep_order(), vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn test_ties_break_by_index() {
+        // 0 -> 3, 1 -> 2
+        let graph = graph(&[(0, 3), (1, 2)]);
+        assert_eq!(graph.topological_order(), Ok(vec![0, 1, 2, 3]));
+    }
+}
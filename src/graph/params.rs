@@ -0,0 +1,101 @@
+use crate::linalg::{matrix::Matrix, vector::Vector};
+
+// Flat view over the scalars of a set of tensors, in a fixed order, so that generic
+// tools can work with any `Function::Params` or `Function::Gradient`.
+pub trait Tensors {
+    fn flatten(&self) -> Vec<f64>;
+    fn flatten_mut(&mut self) -> Vec<&mut f64>;
+
+    // Leaf tensors under their names; a leaf returns itself under an empty name.
+    fn named(&self) -> Vec<(&'static str, &dyn Tensors)>;
+    fn named_mut(&mut self) -> Vec<(&'static str, &mut dyn Tensors)>;
+}
+
+impl Tensors for Matrix {
+    fn flatten(&self) -> Vec<f64> {
+        self.data.iter().flatten().cloned().collect()
+    }
+
+    fn flatten_mut(&mut self) -> Vec<&mut f64> {
+        self.data.iter_mut().flatten().collect()
+    }
+
+    fn named(&self) -> Vec<(&'static str, &dyn Tensors)> {
+        vec![("", self)]
+    }
+
+    fn named_mut(&mut self) -> Vec<(&'static str, &mut dyn Tensors)> {
+        vec![("", self)]
+    }
+}
+
+impl Tensors for Vector {
+    fn flatten(&self) -> Vec<f64> {
+        self.data.clone()
+    }
+
+    fn flatten_mut(&mut self) -> Vec<&mut f64> {
+        self.data.iter_mut().collect()
+    }
+
+    fn named(&self) -> Vec<(&'static str, &dyn Tensors)> {
+        vec![("", self)]
+    }
+
+    fn named_mut(&mut self) -> Vec<(&'static str, &mut dyn Tensors)> {
+        vec![("", self)]
+    }
+}
+
+// Weights and bias of an affine map `x W + b`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LinearParams {
+    pub weights: Matrix,
+    pub bias: Vector,
+}
+
+impl Tensors for LinearParams {
+    fn flatten(&self) -> Vec<f64> {
+        let mut values = self.weights.flatten();
+        values.extend(self.bias.flatten());
+        values
+    }
+
+    fn flatten_mut(&mut self) -> Vec<&mut f64> {
+        let mut values = self.weights.flatten_mut();
+        values.extend(self.bias.flatten_mut());
+        values
+    }
+
+    fn named(&self) -> Vec<(&'static str, &dyn Tensors)> {
+        vec![("weights", &self.weights), ("bias", &self.bias)]
+    }
+
+    fn named_mut(&mut self) -> Vec<(&'static str, &mut dyn Tensors)> {
+        vec![("weights", &mut self.weights), ("bias", &mut self.bias)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_linear_params_named() {
+        let mut params = LinearParams {
+            weights: Matrix::ones(2, 3),
+            bias: Vector::zeros(3),
+        };
+        let names: Vec<&str> = params.named().iter().map(|(name, _)| *name).collect();
+        assert_eq!(names, vec!["weights", "bias"]);
+        assert_eq!(params.flatten().len(), 9);
+
+        for (_, tensor) in params.named_mut() {
+            for value in tensor.flatten_mut() {
+                *value = 2.0;
+            }
+        }
+        assert_eq!(params.weights, Matrix::ones(2, 3) * 2.0);
+        assert_eq!(params.bias, Vector::new(vec![2.0; 3]));
+    }
+}
@@ -0,0 +1,12 @@
+// Order in which `Graph::infer` updates nodes. `Synchronous` computes every delta
+// from the current state before applying any of them (Jacobi). The other schedules
+// sweep the nodes one at a time, so each update already sees its neighbours' new
+// values (Gauss-Seidel).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Schedule {
+    #[default]
+    Synchronous,
+    Topological,
+    Reverse,
+    Random,
+}
@@ -0,0 +1,132 @@
+use rand::Rng;
+
+use crate::graph::{params::LinearParams, Function, Variable};
+use crate::linalg::{init::Init, math::Nonlinearity, matrix::Matrix, vector::Vector};
+
+// Predicts `f(x W) + b` like `GaussianFunction`, but scores the prediction error
+// with an L1 penalty, `|t - pred| / scale + log(2 scale)`, which is robust to outliers.
+pub struct LaplaceFunction {
+    params: LinearParams,
+    activation: Box<dyn Nonlinearity>,
+    scale: f64,
+    fixed: bool,
+}
+
+impl LaplaceFunction {
+    pub fn new(weights: Matrix, bias: Vector, activation: impl Nonlinearity + 'static) -> Self {
+        assert_eq!(weights.cols, bias.size);
+        Self {
+            params: LinearParams { weights, bias },
+            activation: Box::new(activation),
+            scale: 1.0,
+            fixed: false,
+        }
+    }
+
+    pub fn from_init(
+        inp_size: usize,
+        out_size: usize,
+        init: Init,
+        activation: impl Nonlinearity + 'static,
+    ) -> Self {
+        Self::from_init_with_rng(
+            inp_size,
+            out_size,
+            init,
+            activation,
+            &mut rand::thread_rng(),
+        )
+    }
+
+    pub fn from_init_with_rng(
+        inp_size: usize,
+        out_size: usize,
+        init: Init,
+        activation: impl Nonlinearity + 'static,
+        rng: &mut impl Rng,
+    ) -> Self {
+        let weights = Matrix::init_with_rng(inp_size, out_size, init, rng);
+        Self::new(weights, Vector::zeros(out_size), activation)
+    }
+
+    pub fn with_scale(mut self, scale: f64) -> Self {
+        assert!(scale > 0.0);
+        self.scale = scale;
+        self
+    }
+
+    pub fn scale(&self) -> f64 {
+        self.scale
+    }
+
+    pub fn weights(&self) -> &Matrix {
+        &self.params.weights
+    }
+
+    pub fn bias(&self) -> &Vector {
+        &self.params.bias
+    }
+
+    // Returns the pre-activation product, the prediction error, and its scaled sign.
+    fn errors(&self, inp: &Matrix, target: &Matrix) -> (Matrix, Matrix, Matrix) {
+        assert_eq!(inp.cols, self.params.weights.rows);
+        let product = inp.matmul(&self.params.weights);
+        let pred = &self.activation.forward(&product) + &self.params.bias;
+        let err = target - &pred;
+        let scale = self.scale;
+        let sign = err.apply(|v| v.signum() / scale);
+        (product, err, sign)
+    }
+}
+
+impl<T: Variable<Gradient = Matrix>> Function<T> for LaplaceFunction {
+    type Params = LinearParams;
+    type Gradient = LinearParams;
+
+    fn forward(&self, inp: &T) -> Matrix {
+        assert_eq!(inp.data().cols, self.params.weights.rows);
+        let product = inp.data().matmul(&self.params.weights);
+        &self.activation.forward(&product) + &self.params.bias
+    }
+
+    fn energy(&self, inp: &T, target: &T) -> f64 {
+        let (_, err, _) = self.errors(inp.data(), target.data());
+        let count = (err.rows * err.cols) as f64;
+        let abs_err: f64 = err.data.iter().flatten().map(|v| v.abs()).sum();
+        abs_err / self.scale + count * (2.0 * self.scale).ln()
+    }
+
+    fn backward(&self, inp: &T, target: &T) -> (Matrix, Matrix) {
+        let (product, _, sign) = self.errors(inp.data(), target.data());
+        let err_deriv = self.activation.jvp(&product, &sign);
+        let source_deriv = err_deriv.matmul(&self.params.weights.transpose());
+        let target_deriv = -sign;
+        (source_deriv, target_deriv)
+    }
+
+    fn backward_params(&self, inp: &T, target: &T) -> LinearParams {
+        let (product, _, sign) = self.errors(inp.data(), target.data());
+        let err_deriv = self.activation.jvp(&product, &sign);
+        let weight_deriv = inp.data().transpose().matmul(&err_deriv);
+        let bias_deriv = sign.sum(0);
+        LinearParams {
+            weights: weight_deriv,
+            bias: bias_deriv,
+        }
+    }
+
+    fn params(&self) -> &LinearParams {
+        &self.params
+    }
+
+    fn params_mut(&mut self) -> &mut LinearParams {
+        &mut self.params
+    }
+
+    fn update(&mut self, derivatives: LinearParams) {
+        if !self.fixed {
+            self.params.weights += &derivatives.weights.apply(|v| v * 0.001);
+            self.params.bias += &derivatives.bias.apply(|v| v * 0.001);
+        }
+    }
+}
@@ -0,0 +1,3 @@
+use crate::graph::bounded::{BoundedVariable, Real};
+
+pub type LaplaceVariable = BoundedVariable<Real>;
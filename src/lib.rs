@@ -1,7 +1,37 @@
+pub mod bernoulli;
+pub mod categorical;
+pub mod checkpoint;
+pub mod config;
+pub mod data;
 pub mod gaussian;
+pub mod gradcheck;
 pub mod graph;
+pub mod laplace;
 pub mod linalg;
+pub mod poisson;
 
-pub use crate::gaussian::{function::GaussianFunction, variable::GaussianVariable};
-pub use crate::graph::{Function, Graph, Variable};
-pub use crate::linalg::{math::Activation, matrix::Matrix};
+pub use crate::bernoulli::{function::BernoulliFunction, variable::BernoulliVariable};
+pub use crate::categorical::{function::CategoricalFunction, variable::CategoricalVariable};
+pub use crate::config::{Config, ConfigError};
+pub use crate::gaussian::{
+    builder::{GraphBuilder, Handles},
+    function::GaussianFunction,
+    mlp::Mlp,
+    prior::GaussianPrior,
+    variable::GaussianVariable,
+};
+pub use crate::graph::{
+    dynamic::{boxed, DynFunction, DynGraph, DynVariable},
+    mode::Mode,
+    order::CycleError,
+    params::Tensors,
+    schedule::Schedule,
+    Function, Graph, Variable,
+};
+pub use crate::laplace::{function::LaplaceFunction, variable::LaplaceVariable};
+pub use crate::linalg::{
+    init::Init,
+    math::{Activation, Nonlinearity},
+    matrix::Matrix,
+};
+pub use crate::poisson::{function::PoissonFunction, variable::PoissonVariable};
@@ -0,0 +1,61 @@
+use rand::Rng;
+
+use super::matrix::Matrix;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FanMode {
+    FanIn,
+    FanOut,
+}
+
+impl FanMode {
+    pub fn fan(&self, rows: usize, cols: usize) -> usize {
+        match self {
+            FanMode::FanIn => rows,
+            FanMode::FanOut => cols,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Init {
+    Zeros,
+    Ones,
+    Uniform { low: f64, high: f64 },
+    Normal { mean: f64, std: f64 },
+    TruncatedNormal { mean: f64, std: f64 },
+    XavierUniform,
+    XavierNormal,
+    KaimingUniform(FanMode),
+    KaimingNormal(FanMode),
+    LecunUniform,
+    LecunNormal,
+    Orthogonal { gain: f64 },
+    Sparse { sparsity: f64, std: f64 },
+}
+
+impl Init {
+    pub fn sample(&self, rows: usize, cols: usize, rng: &mut impl Rng) -> Matrix {
+        match *self {
+            Init::Zeros => Matrix::zeros(rows, cols),
+            Init::Ones => Matrix::ones(rows, cols),
+            Init::Uniform { low, high } => Matrix::uniform_with_rng(rows, cols, low, high, rng),
+            Init::Normal { mean, std } => Matrix::normal_with_rng(rows, cols, mean, std, rng),
+            Init::TruncatedNormal { mean, std } => {
+                Matrix::truncated_normal_with_rng(rows, cols, mean, std, rng)
+            }
+            Init::XavierUniform => Matrix::xavier_uniform_with_rng(rows, cols, rng),
+            Init::XavierNormal => Matrix::xavier_normal_with_rng(rows, cols, rng),
+            Init::KaimingUniform(mode) => Matrix::kaiming_uniform_with_rng(rows, cols, mode, rng),
+            Init::KaimingNormal(mode) => {
+                Matrix::kaiming_normal_mode_with_rng(rows, cols, mode, rng)
+            }
+            Init::LecunUniform => Matrix::lecun_uniform_with_rng(rows, cols, rng),
+            Init::LecunNormal => Matrix::lecun_normal_with_rng(rows, cols, rng),
+            Init::Orthogonal { gain } => Matrix::orthogonal_with_rng(rows, cols, gain, rng),
+            Init::Sparse { sparsity, std } => {
+                Matrix::sparse_with_rng(rows, cols, sparsity, std, rng)
+            }
+        }
+    }
+}
@@ -1,9 +1,28 @@
 use super::matrix::Matrix;
 
 #[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Activation {
     Linear,
     ReLU,
+    Tanh,
+    Sigmoid,
+    LeakyReLU(f64),
+    ELU(f64),
+    GELU,
+    Softplus,
+    SiLU,
+    Softmax,
+}
+
+pub trait Nonlinearity: Send + Sync {
+    fn forward(&self, input: &Matrix) -> Matrix;
+
+    fn backward(&self, input: &Matrix) -> Matrix;
+
+    fn jvp(&self, input: &Matrix, grad: &Matrix) -> Matrix {
+        grad * &self.backward(input)
+    }
 }
 
 pub struct ActivationFunction {
@@ -14,20 +33,149 @@ impl ActivationFunction {
     pub fn new(activation: Activation) -> Self {
         Self { activation }
     }
+}
 
-    pub fn forward(&self, input: &Matrix) -> Matrix {
+impl Nonlinearity for ActivationFunction {
+    fn forward(&self, input: &Matrix) -> Matrix {
         match self.activation {
             Activation::Linear => input.clone(),
             Activation::ReLU => input.apply(|elem| elem.max(0.0)),
+            Activation::Tanh => input.apply(f64::tanh),
+            Activation::Sigmoid => input.apply(sigmoid),
+            Activation::LeakyReLU(alpha) => {
+                input.apply(|elem| if elem > 0.0 { elem } else { alpha * elem })
+            }
+            Activation::ELU(alpha) => input.apply(|elem| {
+                if elem > 0.0 {
+                    elem
+                } else {
+                    alpha * elem.exp_m1()
+                }
+            }),
+            Activation::GELU => input.apply(gelu),
+            Activation::Softplus => input.apply(softplus),
+            Activation::SiLU => input.apply(|elem| elem * sigmoid(elem)),
+            Activation::Softmax => softmax(input),
         }
     }
 
-    pub fn backward(&self, input: &Matrix) -> Matrix {
+    // Elementwise derivative; for `Softmax` this is the diagonal of the row-wise Jacobian.
+    fn backward(&self, input: &Matrix) -> Matrix {
         match self.activation {
             Activation::Linear => Matrix::ones(input.rows, input.cols),
             Activation::ReLU => input.apply(|elem| if elem > 0.0 { 1.0 } else { 0.0 }),
+            Activation::Tanh => input.apply(|elem| 1.0 - elem.tanh().powi(2)),
+            Activation::Sigmoid => input.apply(|elem| sigmoid(elem) * (1.0 - sigmoid(elem))),
+            Activation::LeakyReLU(alpha) => {
+                input.apply(|elem| if elem > 0.0 { 1.0 } else { alpha })
+            }
+            Activation::ELU(alpha) => {
+                input.apply(|elem| if elem > 0.0 { 1.0 } else { alpha * elem.exp() })
+            }
+            Activation::GELU => input.apply(gelu_deriv),
+            Activation::Softplus => input.apply(sigmoid),
+            Activation::SiLU => input.apply(|elem| {
+                let sig = sigmoid(elem);
+                sig * (1.0 + elem * (1.0 - sig))
+            }),
+            Activation::Softmax => {
+                let probs = softmax(input);
+                &probs * &probs.apply(|p| 1.0 - p)
+            }
         }
     }
+
+    // Product of the Jacobian at `input` with `grad`, which reduces to an elementwise
+    // product for everything except the row-wise `Softmax`.
+    fn jvp(&self, input: &Matrix, grad: &Matrix) -> Matrix {
+        match self.activation {
+            Activation::Softmax => {
+                let probs = softmax(input);
+                let mut result = Matrix::zeros(input.rows, input.cols);
+                for i in 0..input.rows {
+                    let dot: f64 = (0..input.cols)
+                        .map(|j| probs.data[i][j] * grad.data[i][j])
+                        .sum();
+                    for j in 0..input.cols {
+                        result.data[i][j] = probs.data[i][j] * (grad.data[i][j] - dot);
+                    }
+                }
+                result
+            }
+            _ => grad * &self.backward(input),
+        }
+    }
+}
+
+impl Nonlinearity for Activation {
+    fn forward(&self, input: &Matrix) -> Matrix {
+        ActivationFunction::new(*self).forward(input)
+    }
+
+    fn backward(&self, input: &Matrix) -> Matrix {
+        ActivationFunction::new(*self).backward(input)
+    }
+
+    fn jvp(&self, input: &Matrix, grad: &Matrix) -> Matrix {
+        ActivationFunction::new(*self).jvp(input, grad)
+    }
+}
+
+impl Nonlinearity for Box<dyn Nonlinearity> {
+    fn forward(&self, input: &Matrix) -> Matrix {
+        self.as_ref().forward(input)
+    }
+
+    fn backward(&self, input: &Matrix) -> Matrix {
+        self.as_ref().backward(input)
+    }
+
+    fn jvp(&self, input: &Matrix, grad: &Matrix) -> Matrix {
+        self.as_ref().jvp(input, grad)
+    }
+}
+
+fn sigmoid(x: f64) -> f64 {
+    if x >= 0.0 {
+        1.0 / (1.0 + (-x).exp())
+    } else {
+        let e = x.exp();
+        e / (1.0 + e)
+    }
+}
+
+fn softplus(x: f64) -> f64 {
+    x.max(0.0) + (-x.abs()).exp().ln_1p()
+}
+
+const GELU_COEFF: f64 = 0.044715;
+const SQRT_2_OVER_PI: f64 = 0.797_884_560_802_865_4;
+
+// Tanh approximation of GELU.
+fn gelu(x: f64) -> f64 {
+    let inner = SQRT_2_OVER_PI * (x + GELU_COEFF * x.powi(3));
+    0.5 * x * (1.0 + inner.tanh())
+}
+
+fn gelu_deriv(x: f64) -> f64 {
+    let inner = SQRT_2_OVER_PI * (x + GELU_COEFF * x.powi(3));
+    let tanh = inner.tanh();
+    let inner_deriv = SQRT_2_OVER_PI * (1.0 + 3.0 * GELU_COEFF * x.powi(2));
+    0.5 * (1.0 + tanh) + 0.5 * x * (1.0 - tanh.powi(2)) * inner_deriv
+}
+
+fn softmax(input: &Matrix) -> Matrix {
+    let data = input
+        .data
+        .iter()
+        .map(|row| {
+            let max = row.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+            let exps: Vec<f64> = row.iter().map(|v| (v - max).exp()).collect();
+            let total: f64 = exps.iter().sum();
+            exps.iter().map(|v| v / total).collect()
+        })
+        .collect();
+    Matrix::new(data)
 }
 
 #[cfg(test)]
@@ -68,4 +216,98 @@ mod tests {
         let expected = Matrix::new(vec![vec![0.0, 1.0], vec![1.0, 0.0]]);
         assert_eq!(output, expected);
     }
+
+    fn all_activations() -> Vec<Activation> {
+        vec![
+            Activation::Linear,
+            Activation::ReLU,
+            Activation::Tanh,
+            Activation::Sigmoid,
+            Activation::LeakyReLU(0.1),
+            Activation::ELU(1.0),
+            Activation::GELU,
+            Activation::Softplus,
+            Activation::SiLU,
+            Activation::Softmax,
+        ]
+    }
+
+    fn numerical_jvp(activation: &ActivationFunction, input: &Matrix, grad: &Matrix) -> Matrix {
+        let eps = 1e-6;
+        let mut result = Matrix::zeros(input.rows, input.cols);
+        for i in 0..input.rows {
+            for j in 0..input.cols {
+                let mut plus = input.clone();
+                let mut minus = input.clone();
+                plus.data[i][j] += eps;
+                minus.data[i][j] -= eps;
+                let diff = &activation.forward(&plus) - &activation.forward(&minus);
+                result.data[i][j] = (&diff * grad).data.iter().flatten().sum::<f64>() / (2.0 * eps);
+            }
+        }
+        result
+    }
+
+    #[test]
+    fn test_numerical_derivatives() {
+        let input = Matrix::new(vec![vec![-1.3, 0.4, 2.1], vec![0.7, -0.2, -2.5]]);
+        let grad = Matrix::new(vec![vec![0.5, -1.0, 0.3], vec![1.2, 0.8, -0.6]]);
+        for activation in all_activations() {
+            let function = ActivationFunction::new(activation);
+            let analytic = function.jvp(&input, &grad);
+            let numerical = numerical_jvp(&function, &input, &grad);
+            for (a, n) in analytic
+                .data
+                .iter()
+                .flatten()
+                .zip(numerical.data.iter().flatten())
+            {
+                assert!((a - n).abs() < 1e-6, "{:?}: {} != {}", activation, a, n);
+            }
+        }
+    }
+
+    #[test]
+    fn test_softmax_rows_sum_to_one() {
+        let activation = ActivationFunction::new(Activation::Softmax);
+        let input = Matrix::new(vec![vec![1.0, 2.0, 3.0], vec![1000.0, 0.0, -1000.0]]);
+        let output = activation.forward(&input);
+        for total in output.sum(1).data {
+            assert!((total - 1.0).abs() < 1e-12);
+        }
+    }
+
+    struct Cube;
+
+    impl Nonlinearity for Cube {
+        fn forward(&self, input: &Matrix) -> Matrix {
+            input.apply(|elem| elem.powi(3))
+        }
+
+        fn backward(&self, input: &Matrix) -> Matrix {
+            input.apply(|elem| 3.0 * elem.powi(2))
+        }
+    }
+
+    #[test]
+    fn test_custom_nonlinearity() {
+        let activation: Box<dyn Nonlinearity> = Box::new(Cube);
+        let input = Matrix::new(vec![vec![-1.0, 2.0], vec![0.5, -3.0]]);
+        let grad = Matrix::new(vec![vec![1.0, 0.5], vec![2.0, -1.0]]);
+        let output = activation.forward(&input);
+        let expected = Matrix::new(vec![vec![-1.0, 8.0], vec![0.125, -27.0]]);
+        assert_eq!(output, expected);
+        let output = activation.jvp(&input, &grad);
+        let expected = Matrix::new(vec![vec![3.0, 6.0], vec![1.5, -27.0]]);
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn test_leaky_relu_forward() {
+        let activation = ActivationFunction::new(Activation::LeakyReLU(0.1));
+        let input = Matrix::new(vec![vec![-1.0, 2.0], vec![3.0, -4.0]]);
+        let output = activation.forward(&input);
+        let expected = Matrix::new(vec![vec![-0.1, 2.0], vec![3.0, -0.4]]);
+        assert_eq!(output, expected);
+    }
 }
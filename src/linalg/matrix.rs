@@ -1,10 +1,11 @@
 use std::fmt;
 use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
 
-use rand::Rng;
+use rand::{seq::index, Rng};
+use rand_distr::{Distribution, Normal, Uniform};
 use rayon::prelude::*;
-use rand_distr::{Distribution, Normal};
 
+use super::init::{FanMode, Init};
 use super::vector::Vector;
 
 pub struct Matrix {
@@ -38,8 +39,8 @@ impl Matrix {
 
     pub fn identity(size: usize) -> Self {
         let mut data = vec![vec![0.0; size]; size];
-        for i in 0..size {
-            data[i][i] = 1.0;
+        for (i, row) in data.iter_mut().enumerate() {
+            row[i] = 1.0;
         }
         Self {
             rows: size,
@@ -49,7 +50,10 @@ impl Matrix {
     }
 
     pub fn random(rows: usize, cols: usize) -> Self {
-        let mut rng = rand::thread_rng();
+        Self::random_with_rng(rows, cols, &mut rand::thread_rng())
+    }
+
+    pub fn random_with_rng(rows: usize, cols: usize, rng: &mut impl Rng) -> Self {
         let data = (0..rows)
             .map(|_| (0..cols).map(|_| rng.gen::<f64>()).collect())
             .collect();
@@ -57,28 +61,237 @@ impl Matrix {
     }
 
     pub fn normal(rows: usize, cols: usize, mean: f64, std: f64) -> Self {
+        Self::normal_with_rng(rows, cols, mean, std, &mut rand::thread_rng())
+    }
+
+    pub fn normal_with_rng(
+        rows: usize,
+        cols: usize,
+        mean: f64,
+        std: f64,
+        rng: &mut impl Rng,
+    ) -> Self {
         let normal = Normal::new(mean, std).unwrap();
-        let mut rng = rand::thread_rng();
 
         let data = (0..rows)
-            .map(|_| (0..cols).map(|_| normal.sample(&mut rng)).collect())
+            .map(|_| (0..cols).map(|_| normal.sample(rng)).collect())
             .collect();
 
         Self { rows, cols, data }
     }
 
     pub fn kaiming_normal(rows: usize, cols: usize) -> Self {
-        let kappa = f64::sqrt(2.0 / rows as f64);
-        let normal = Normal::new(0.0, kappa).unwrap();
-        let mut rng = rand::thread_rng();
+        Self::kaiming_normal_with_rng(rows, cols, &mut rand::thread_rng())
+    }
+
+    pub fn kaiming_normal_with_rng(rows: usize, cols: usize, rng: &mut impl Rng) -> Self {
+        Self::kaiming_normal_mode_with_rng(rows, cols, FanMode::FanIn, rng)
+    }
+
+    pub fn kaiming_normal_mode(rows: usize, cols: usize, mode: FanMode) -> Self {
+        Self::kaiming_normal_mode_with_rng(rows, cols, mode, &mut rand::thread_rng())
+    }
+
+    pub fn kaiming_normal_mode_with_rng(
+        rows: usize,
+        cols: usize,
+        mode: FanMode,
+        rng: &mut impl Rng,
+    ) -> Self {
+        let kappa = f64::sqrt(2.0 / mode.fan(rows, cols) as f64);
+        Self::normal_with_rng(rows, cols, 0.0, kappa, rng)
+    }
+
+    pub fn kaiming_uniform(rows: usize, cols: usize, mode: FanMode) -> Self {
+        Self::kaiming_uniform_with_rng(rows, cols, mode, &mut rand::thread_rng())
+    }
+
+    pub fn kaiming_uniform_with_rng(
+        rows: usize,
+        cols: usize,
+        mode: FanMode,
+        rng: &mut impl Rng,
+    ) -> Self {
+        let bound = f64::sqrt(6.0 / mode.fan(rows, cols) as f64);
+        Self::uniform_with_rng(rows, cols, -bound, bound, rng)
+    }
+
+    pub fn uniform(rows: usize, cols: usize, low: f64, high: f64) -> Self {
+        Self::uniform_with_rng(rows, cols, low, high, &mut rand::thread_rng())
+    }
+
+    pub fn uniform_with_rng(
+        rows: usize,
+        cols: usize,
+        low: f64,
+        high: f64,
+        rng: &mut impl Rng,
+    ) -> Self {
+        let uniform = Uniform::new(low, high);
 
         let data = (0..rows)
-            .map(|_| (0..cols).map(|_| normal.sample(&mut rng)).collect())
+            .map(|_| (0..cols).map(|_| uniform.sample(rng)).collect())
             .collect();
 
         Self { rows, cols, data }
     }
 
+    pub fn truncated_normal(rows: usize, cols: usize, mean: f64, std: f64) -> Self {
+        Self::truncated_normal_with_rng(rows, cols, mean, std, &mut rand::thread_rng())
+    }
+
+    // Samples are redrawn until they fall within two standard deviations of the mean.
+    pub fn truncated_normal_with_rng(
+        rows: usize,
+        cols: usize,
+        mean: f64,
+        std: f64,
+        rng: &mut impl Rng,
+    ) -> Self {
+        let normal = Normal::new(mean, std).unwrap();
+        let (low, high) = (mean - 2.0 * std, mean + 2.0 * std);
+        let mut sample = || loop {
+            let value = normal.sample(rng);
+            if value >= low && value <= high {
+                return value;
+            }
+        };
+
+        let data = (0..rows)
+            .map(|_| (0..cols).map(|_| sample()).collect())
+            .collect();
+
+        Self { rows, cols, data }
+    }
+
+    pub fn xavier_uniform(rows: usize, cols: usize) -> Self {
+        Self::xavier_uniform_with_rng(rows, cols, &mut rand::thread_rng())
+    }
+
+    pub fn xavier_uniform_with_rng(rows: usize, cols: usize, rng: &mut impl Rng) -> Self {
+        let bound = f64::sqrt(6.0 / (rows + cols) as f64);
+        Self::uniform_with_rng(rows, cols, -bound, bound, rng)
+    }
+
+    pub fn xavier_normal(rows: usize, cols: usize) -> Self {
+        Self::xavier_normal_with_rng(rows, cols, &mut rand::thread_rng())
+    }
+
+    pub fn xavier_normal_with_rng(rows: usize, cols: usize, rng: &mut impl Rng) -> Self {
+        let std = f64::sqrt(2.0 / (rows + cols) as f64);
+        Self::normal_with_rng(rows, cols, 0.0, std, rng)
+    }
+
+    pub fn lecun_uniform(rows: usize, cols: usize) -> Self {
+        Self::lecun_uniform_with_rng(rows, cols, &mut rand::thread_rng())
+    }
+
+    pub fn lecun_uniform_with_rng(rows: usize, cols: usize, rng: &mut impl Rng) -> Self {
+        let bound = f64::sqrt(3.0 / rows as f64);
+        Self::uniform_with_rng(rows, cols, -bound, bound, rng)
+    }
+
+    pub fn lecun_normal(rows: usize, cols: usize) -> Self {
+        Self::lecun_normal_with_rng(rows, cols, &mut rand::thread_rng())
+    }
+
+    pub fn lecun_normal_with_rng(rows: usize, cols: usize, rng: &mut impl Rng) -> Self {
+        let std = f64::sqrt(1.0 / rows as f64);
+        Self::normal_with_rng(rows, cols, 0.0, std, rng)
+    }
+
+    pub fn orthogonal(rows: usize, cols: usize, gain: f64) -> Self {
+        Self::orthogonal_with_rng(rows, cols, gain, &mut rand::thread_rng())
+    }
+
+    // Gram-Schmidt on a Gaussian matrix, orthonormalising along the longer dimension
+    // so that either the rows or the columns of the result are orthonormal.
+    pub fn orthogonal_with_rng(rows: usize, cols: usize, gain: f64, rng: &mut impl Rng) -> Self {
+        let (long, short) = if rows >= cols {
+            (rows, cols)
+        } else {
+            (cols, rows)
+        };
+        let mut basis = Self::normal_with_rng(short, long, 0.0, 1.0, rng);
+
+        for i in 0..short {
+            for k in 0..i {
+                let dot: f64 = (0..long).map(|j| basis.data[i][j] * basis.data[k][j]).sum();
+                for j in 0..long {
+                    basis.data[i][j] -= dot * basis.data[k][j];
+                }
+            }
+            let norm = basis.data[i].iter().map(|v| v * v).sum::<f64>().sqrt();
+            for j in 0..long {
+                basis.data[i][j] /= norm;
+            }
+        }
+
+        let basis = basis.apply(|v| v * gain);
+        if rows >= cols {
+            basis.transpose()
+        } else {
+            basis
+        }
+    }
+
+    pub fn sparse(rows: usize, cols: usize, sparsity: f64, std: f64) -> Self {
+        Self::sparse_with_rng(rows, cols, sparsity, std, &mut rand::thread_rng())
+    }
+
+    // Each column has `ceil(sparsity * rows)` entries set to zero.
+    pub fn sparse_with_rng(
+        rows: usize,
+        cols: usize,
+        sparsity: f64,
+        std: f64,
+        rng: &mut impl Rng,
+    ) -> Self {
+        assert!((0.0..=1.0).contains(&sparsity));
+        let num_zeros = (sparsity * rows as f64).ceil() as usize;
+        let mut result = Self::normal_with_rng(rows, cols, 0.0, std, rng);
+        for j in 0..cols {
+            for i in index::sample(rng, rows, num_zeros) {
+                result.data[i][j] = 0.0;
+            }
+        }
+        result
+    }
+
+    pub fn init(rows: usize, cols: usize, init: Init) -> Self {
+        Self::init_with_rng(rows, cols, init, &mut rand::thread_rng())
+    }
+
+    pub fn init_with_rng(rows: usize, cols: usize, init: Init, rng: &mut impl Rng) -> Self {
+        init.sample(rows, cols, rng)
+    }
+
+    // Lower-triangular `L` with `L L^T = self`, or `None` if the matrix is not
+    // symmetric positive definite.
+    pub fn cholesky(&self) -> Option<Matrix> {
+        assert_eq!(self.rows, self.cols);
+        let size = self.rows;
+        let mut factor = Matrix::zeros(size, size);
+        for i in 0..size {
+            for j in 0..=i {
+                if (self.data[i][j] - self.data[j][i]).abs() > 1e-9 {
+                    return None;
+                }
+                let dot: f64 = (0..j).map(|k| factor.data[i][k] * factor.data[j][k]).sum();
+                if i == j {
+                    let value = self.data[i][i] - dot;
+                    if value <= 0.0 {
+                        return None;
+                    }
+                    factor.data[i][i] = value.sqrt();
+                } else {
+                    factor.data[i][j] = (self.data[i][j] - dot) / factor.data[j][j];
+                }
+            }
+        }
+        Some(factor)
+    }
+
     pub fn mean(&self) -> f64 {
         let total_elements = (self.rows * self.cols) as f64;
         let sum: f64 = self.data.iter().flat_map(|row| row.iter()).cloned().sum();
@@ -103,8 +316,8 @@ impl Matrix {
             .par_iter_mut()
             .enumerate()
             .for_each(|(j, row)| {
-                for i in 0..self.rows {
-                    row[i] = self.data[i][j];
+                for (value, source) in row.iter_mut().zip(self.data.iter()) {
+                    *value = source[j];
                 }
             });
         Self::new(transposed_data)
@@ -114,9 +327,9 @@ impl Matrix {
         assert_eq!(self.cols, other.rows);
         let mut result = Matrix::zeros(self.rows, other.cols);
         result.data.par_iter_mut().enumerate().for_each(|(i, row)| {
-            for j in 0..other.cols {
-                for k in 0..self.cols {
-                    row[j] += self.data[i][k] * other.data[k][j];
+            for (j, value) in row.iter_mut().enumerate() {
+                for (a, other_row) in self.data[i].iter().zip(other.data.iter()) {
+                    *value += a * other_row[j];
                 }
             }
         });
@@ -127,20 +340,15 @@ impl Matrix {
         match axis {
             0 => {
                 let mut result = vec![0.0; self.cols];
-                for i in 0..self.rows {
-                    for j in 0..self.cols {
-                        result[j] += self.data[i][j];
+                for row in self.data.iter() {
+                    for (total, value) in result.iter_mut().zip(row.iter()) {
+                        *total += value;
                     }
                 }
                 Vector::new(result)
             }
             1 => {
-                let mut result = vec![0.0; self.rows];
-                for i in 0..self.rows {
-                    for j in 0..self.cols {
-                        result[i] += self.data[i][j];
-                    }
-                }
+                let result = self.data.iter().map(|row| row.iter().sum()).collect();
                 Vector::new(result)
             }
             _ => panic!("Axis {} is not supported.", axis),
@@ -148,7 +356,7 @@ impl Matrix {
     }
 }
 
-impl<'a, 'b> Add<&'b Matrix> for &'a Matrix {
+impl<'b> Add<&'b Matrix> for &Matrix {
     type Output = Matrix;
 
     fn add(self, other: &'b Matrix) -> Self::Output {
@@ -164,7 +372,7 @@ impl<'a, 'b> Add<&'b Matrix> for &'a Matrix {
     }
 }
 
-impl<'a, 'b> Add<&'b Vector> for &'a Matrix {
+impl<'b> Add<&'b Vector> for &Matrix {
     type Output = Matrix;
 
     fn add(self, vector: &'b Vector) -> Self::Output {
@@ -206,7 +414,7 @@ impl AddAssign<&Matrix> for Matrix {
     }
 }
 
-impl<'a, 'b> Mul<&'b Matrix> for &'a Matrix {
+impl<'b> Mul<&'b Matrix> for &Matrix {
     type Output = Matrix;
 
     fn mul(self, other: &'b Matrix) -> Self::Output {
@@ -214,14 +422,30 @@ impl<'a, 'b> Mul<&'b Matrix> for &'a Matrix {
         assert_eq!(self.cols, other.cols);
         let mut result = Matrix::zeros(self.rows, self.cols);
         result.data.par_iter_mut().enumerate().for_each(|(i, row)| {
-            for j in 0..self.cols {
-                row[j] = self.data[i][j] * other.data[i][j];
+            for ((value, a), b) in row.iter_mut().zip(&self.data[i]).zip(&other.data[i]) {
+                *value = a * b;
             }
         });
         result
     }
 }
 
+impl<'b> Mul<&'b Vector> for &Matrix {
+    type Output = Matrix;
+
+    fn mul(self, vector: &'b Vector) -> Self::Output {
+        assert_eq!(self.cols, vector.size);
+
+        let mut result = Matrix::zeros(self.rows, self.cols);
+        for i in 0..self.rows {
+            for j in 0..self.cols {
+                result.data[i][j] = self.data[i][j] * vector.data[j];
+            }
+        }
+        result
+    }
+}
+
 impl Mul<f64> for Matrix {
     type Output = Matrix;
 
@@ -236,7 +460,7 @@ impl Mul<f64> for Matrix {
     }
 }
 
-impl<'a, 'b> Sub<&'b Matrix> for &'a Matrix {
+impl<'b> Sub<&'b Matrix> for &Matrix {
     type Output = Matrix;
 
     fn sub(self, other: &'b Matrix) -> Self::Output {
@@ -320,7 +544,10 @@ impl fmt::Debug for Matrix {
 
 #[cfg(test)]
 mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
     use super::Matrix;
+    use crate::linalg::init::{FanMode, Init};
 
     #[test]
     fn test_new() {
@@ -385,6 +612,113 @@ mod tests {
         assert_eq!(transposed_matrix.cols, 2);
     }
 
+    #[test]
+    fn test_normal_with_rng_is_reproducible() {
+        let a = Matrix::normal_with_rng(3, 4, 0.0, 1.0, &mut StdRng::seed_from_u64(7));
+        let b = Matrix::normal_with_rng(3, 4, 0.0, 1.0, &mut StdRng::seed_from_u64(7));
+        let c = Matrix::normal_with_rng(3, 4, 0.0, 1.0, &mut StdRng::seed_from_u64(8));
+        assert_eq!(a.data, b.data);
+        assert_ne!(a.data, c.data);
+    }
+
+    #[test]
+    fn test_uniform_bounds() {
+        let matrix = Matrix::uniform_with_rng(20, 20, -0.5, 0.5, &mut StdRng::seed_from_u64(0));
+        assert!(matrix
+            .data
+            .iter()
+            .flatten()
+            .all(|&v| (-0.5..0.5).contains(&v)));
+    }
+
+    #[test]
+    fn test_truncated_normal_bounds() {
+        let matrix =
+            Matrix::truncated_normal_with_rng(50, 50, 1.0, 0.5, &mut StdRng::seed_from_u64(0));
+        assert!(matrix
+            .data
+            .iter()
+            .flatten()
+            .all(|&v| (0.0..=2.0).contains(&v)));
+    }
+
+    #[test]
+    fn test_orthogonal_tall() {
+        let matrix = Matrix::orthogonal_with_rng(6, 3, 1.0, &mut StdRng::seed_from_u64(0));
+        let gram = matrix.transpose().matmul(&matrix);
+        assert_close(&gram, &Matrix::identity(3));
+    }
+
+    #[test]
+    fn test_orthogonal_wide() {
+        let matrix = Matrix::orthogonal_with_rng(3, 6, 2.0, &mut StdRng::seed_from_u64(0));
+        let gram = matrix.matmul(&matrix.transpose());
+        assert_close(&gram, &(Matrix::identity(3) * 4.0));
+    }
+
+    #[test]
+    fn test_sparse_zeros_per_column() {
+        let matrix = Matrix::sparse_with_rng(10, 4, 0.25, 1.0, &mut StdRng::seed_from_u64(0));
+        for j in 0..4 {
+            let zeros = (0..10).filter(|&i| matrix.data[i][j] == 0.0).count();
+            assert_eq!(zeros, 3);
+        }
+    }
+
+    #[test]
+    fn test_init_shapes() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let inits = vec![
+            Init::Zeros,
+            Init::Uniform {
+                low: -1.0,
+                high: 1.0,
+            },
+            Init::Normal {
+                mean: 0.0,
+                std: 1.0,
+            },
+            Init::TruncatedNormal {
+                mean: 0.0,
+                std: 1.0,
+            },
+            Init::XavierUniform,
+            Init::XavierNormal,
+            Init::KaimingUniform(FanMode::FanOut),
+            Init::KaimingNormal(FanMode::FanIn),
+            Init::LecunUniform,
+            Init::LecunNormal,
+            Init::Orthogonal { gain: 1.0 },
+            Init::Sparse {
+                sparsity: 0.1,
+                std: 0.01,
+            },
+        ];
+        for init in inits {
+            let matrix = Matrix::init_with_rng(5, 7, init, &mut rng);
+            assert_eq!((matrix.rows, matrix.cols), (5, 7));
+            assert_eq!(matrix.data.len(), 5);
+            assert!(matrix.data.iter().all(|row| row.len() == 7));
+        }
+    }
+
+    #[test]
+    fn test_cholesky() {
+        let matrix = Matrix::new(vec![vec![4.0, 2.0], vec![2.0, 3.0]]);
+        let factor = matrix.cholesky().unwrap();
+        assert_eq!(factor.data[0][1], 0.0);
+        assert_close(&factor.matmul(&factor.transpose()), &matrix);
+        assert!(Matrix::new(vec![vec![1.0, 2.0], vec![2.0, 1.0]])
+            .cholesky()
+            .is_none());
+    }
+
+    fn assert_close(a: &Matrix, b: &Matrix) {
+        for (x, y) in a.data.iter().flatten().zip(b.data.iter().flatten()) {
+            assert!((x - y).abs() < 1e-9, "{} != {}", x, y);
+        }
+    }
+
     #[test]
     #[should_panic]
     fn test_add_incompatible_matrices() {
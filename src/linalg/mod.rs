@@ -1,3 +1,4 @@
+pub mod init;
 pub mod math;
 pub mod matrix;
 pub mod vector;
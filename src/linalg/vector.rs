@@ -28,7 +28,10 @@ impl Vector {
     }
 
     pub fn random(size: usize) -> Self {
-        let mut rng = rand::thread_rng();
+        Self::random_with_rng(size, &mut rand::thread_rng())
+    }
+
+    pub fn random_with_rng(size: usize, rng: &mut impl Rng) -> Self {
         let data = (0..size).map(|_| rng.gen::<f64>()).collect();
         Self { size, data }
     }
@@ -60,6 +63,24 @@ impl Clone for Vector {
     }
 }
 
+impl PartialEq for Vector {
+    fn eq(&self, other: &Self) -> bool {
+        if self.size != other.size {
+            return false;
+        }
+
+        for i in 0..self.size {
+            if (self.data[i] - other.data[i]).abs() > f64::EPSILON {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+impl Eq for Vector {}
+
 impl fmt::Debug for Vector {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("Vector")
@@ -0,0 +1,98 @@
+use rand::Rng;
+
+use crate::graph::{params::LinearParams, Function, Variable};
+use crate::linalg::{init::Init, matrix::Matrix, vector::Vector};
+
+// Predicts Poisson rates `exp(x W + b)` through a log link. The energy is the
+// negative log-likelihood without the `log(t!)` term, which only depends on the
+// observed counts.
+pub struct PoissonFunction {
+    params: LinearParams,
+    fixed: bool,
+}
+
+impl PoissonFunction {
+    pub fn new(weights: Matrix, bias: Vector) -> Self {
+        assert_eq!(weights.cols, bias.size);
+        Self {
+            params: LinearParams { weights, bias },
+            fixed: false,
+        }
+    }
+
+    pub fn from_init(inp_size: usize, out_size: usize, init: Init) -> Self {
+        Self::from_init_with_rng(inp_size, out_size, init, &mut rand::thread_rng())
+    }
+
+    pub fn from_init_with_rng(
+        inp_size: usize,
+        out_size: usize,
+        init: Init,
+        rng: &mut impl Rng,
+    ) -> Self {
+        let weights = Matrix::init_with_rng(inp_size, out_size, init, rng);
+        Self::new(weights, Vector::zeros(out_size))
+    }
+
+    pub fn weights(&self) -> &Matrix {
+        &self.params.weights
+    }
+
+    pub fn bias(&self) -> &Vector {
+        &self.params.bias
+    }
+
+    fn log_rates(&self, inp: &Matrix) -> Matrix {
+        assert_eq!(inp.cols, self.params.weights.rows);
+        &inp.matmul(&self.params.weights) + &self.params.bias
+    }
+}
+
+impl<T: Variable<Gradient = Matrix>> Function<T> for PoissonFunction {
+    type Params = LinearParams;
+    type Gradient = LinearParams;
+
+    fn forward(&self, inp: &T) -> Matrix {
+        self.log_rates(inp.data()).apply(f64::exp)
+    }
+
+    fn energy(&self, inp: &T, target: &T) -> f64 {
+        let log_rates = self.log_rates(inp.data());
+        let energy = &log_rates.apply(f64::exp) - &(target.data() * &log_rates);
+        energy.data.iter().flatten().sum()
+    }
+
+    fn backward(&self, inp: &T, target: &T) -> (Matrix, Matrix) {
+        let log_rates = self.log_rates(inp.data());
+        let err = target.data() - &log_rates.apply(f64::exp);
+        let source_deriv = err.matmul(&self.params.weights.transpose());
+        let target_deriv = log_rates;
+        (source_deriv, target_deriv)
+    }
+
+    fn backward_params(&self, inp: &T, target: &T) -> LinearParams {
+        let log_rates = self.log_rates(inp.data());
+        let err = target.data() - &log_rates.apply(f64::exp);
+        let weight_deriv = inp.data().transpose().matmul(&err);
+        let bias_deriv = err.sum(0);
+        LinearParams {
+            weights: weight_deriv,
+            bias: bias_deriv,
+        }
+    }
+
+    fn params(&self) -> &LinearParams {
+        &self.params
+    }
+
+    fn params_mut(&mut self) -> &mut LinearParams {
+        &mut self.params
+    }
+
+    fn update(&mut self, derivatives: LinearParams) {
+        if !self.fixed {
+            self.params.weights += &derivatives.weights.apply(|v| v * 0.001);
+            self.params.bias += &derivatives.bias.apply(|v| v * 0.001);
+        }
+    }
+}
@@ -0,0 +1,4 @@
+use crate::graph::bounded::{BoundedVariable, NonNegative};
+
+// Holds non-negative rates; observed nodes are clamped to event counts.
+pub type PoissonVariable = BoundedVariable<NonNegative>;